@@ -73,4 +73,4 @@
 
 pub mod types;
 
-pub use types::{DependencyError, StateError, TransitionError};
+pub use types::{DependencyError, StateError, TransitionError, ValidationError, ValidationErrors};
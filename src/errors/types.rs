@@ -34,7 +34,10 @@ use thiserror::Error;
 ///     Ok("resource".to_string())
 /// }
 /// ```
-#[derive(Debug, Error, Clone)]
+///
+/// Unlike the other example enums, `StateError` is not `Clone` because its
+/// [`StateError::Io`] variant wraps `std::io::Error`.
+#[derive(Debug, Error)]
 pub enum StateError {
     /// Resource with the given ID was not found
     #[error("Resource not found: {0}")]
@@ -79,6 +82,33 @@ pub enum StateError {
     /// Internal lock was poisoned due to a prior panic
     #[error("Internal lock poisoned: {lock}")]
     LockPoisoned { lock: String },
+
+    /// IO error while reading or writing state
+    ///
+    /// `std::io::Error` is not `Clone`, so including it means `StateError`
+    /// cannot derive `Clone`. Callers that need to duplicate an error should
+    /// convert it to a string (or wrap it in an `Arc`) first.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl StateError {
+    /// Stable machine-readable code for this error, independent of the message text.
+    pub fn code(&self) -> &'static str {
+        match self {
+            StateError::NotFound(_) => "state.not_found",
+            StateError::InvalidState { .. } => "state.invalid_state",
+            StateError::DependencyNotSatisfied { .. } => "state.dependency_not_satisfied",
+            StateError::IncompleteDependencies(_) => "state.incomplete_dependencies",
+            StateError::NoResourcesReady => "state.no_resources_ready",
+            StateError::AlreadyInProgress(_) => "state.already_in_progress",
+            StateError::MultipleErrors(_) => "state.multiple_errors",
+            StateError::DuplicateId(_) => "state.duplicate_id",
+            StateError::TransitionError(_) => "state.transition",
+            StateError::LockPoisoned { .. } => "state.lock_poisoned",
+            StateError::Io(_) => "state.io",
+        }
+    }
 }
 
 /// Example: Errors related to state transitions.
@@ -137,6 +167,19 @@ pub enum TransitionError {
     },
 }
 
+impl TransitionError {
+    /// Stable machine-readable code for this error, independent of the message text.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TransitionError::NotFound(_) => "transition.not_found",
+            TransitionError::NotInExpectedState { .. } => "transition.not_in_expected_state",
+            TransitionError::DependencyNotFound { .. } => "transition.dependency_not_found",
+            TransitionError::DependencyNotSatisfied { .. } => "transition.dependency_not_satisfied",
+            TransitionError::InvalidTransition { .. } => "transition.invalid",
+        }
+    }
+}
+
 /// Example: Errors related to dependency resolution.
 ///
 /// This enum demonstrates how to model dependency graph errors,
@@ -185,6 +228,21 @@ pub enum DependencyError {
     TopologicalSortFailed(String),
 }
 
+impl DependencyError {
+    /// Stable machine-readable code for this error, independent of the message text.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DependencyError::CircularDependency => "dep.circular",
+            DependencyError::CycleDetected(_) => "dep.cycle",
+            DependencyError::DependencyNotFound { .. } => "dep.not_found",
+            DependencyError::InvalidDependency { .. } => "dep.invalid",
+            DependencyError::DependencyOnDescendant { .. } => "dep.on_descendant",
+            DependencyError::NoReadyResources => "dep.no_ready_resources",
+            DependencyError::TopologicalSortFailed(_) => "dep.topological_sort_failed",
+        }
+    }
+}
+
 /// Example: Validation errors for input data.
 ///
 /// This enum demonstrates how to model validation errors with detailed
@@ -217,6 +275,81 @@ pub enum ValidationError {
     InvalidFormat { field: String, reason: String },
 }
 
+/// Example: Collecting several validation errors into one result.
+///
+/// This supports a "report everything wrong at once" style, where a tool
+/// handler checks every field before failing.
+///
+/// # Usage
+///
+/// ```rust
+/// use mcp_tools::errors::{ValidationError, ValidationErrors};
+///
+/// fn validate(name: &str, count: i64) -> Result<(), ValidationErrors> {
+///     let mut errors = ValidationErrors::new();
+///     if name.is_empty() {
+///         errors.push(ValidationError::MissingField("name".to_string()));
+///     }
+///     if count < 0 {
+///         errors.push(ValidationError::InvalidValue {
+///             field: "count".to_string(),
+///             reason: "must be non-negative".to_string(),
+///         });
+///     }
+///     errors.into_result(())
+/// }
+///
+/// assert_eq!(validate("", -1).unwrap_err().len(), 2);
+/// assert!(validate("ok", 1).is_ok());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ValidationErrors(pub Vec<ValidationError>);
+
+impl ValidationErrors {
+    /// Create an empty collector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a validation error.
+    pub fn push(&mut self, error: ValidationError) {
+        self.0.push(error);
+    }
+
+    /// Whether no errors have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Number of recorded errors.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Return `Ok(ok)` if no errors were recorded, otherwise `Err(self)`.
+    pub fn into_result<T>(self, ok: T) -> Result<T, ValidationErrors> {
+        if self.is_empty() {
+            Ok(ok)
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, error) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -234,6 +367,21 @@ mod tests {
         assert!(matches!(state_err, StateError::TransitionError(_)));
     }
 
+    #[test]
+    fn test_io_error_conversion() {
+        fn read_state() -> Result<(), StateError> {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "state.json",
+            ))?;
+            Ok(())
+        }
+
+        let err = read_state().unwrap_err();
+        assert!(matches!(err, StateError::Io(_)));
+        assert_eq!(err.to_string(), "IO error: state.json");
+    }
+
     #[test]
     fn test_dependency_error_display() {
         let err = DependencyError::CircularDependency;
@@ -243,9 +391,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(
+            StateError::NotFound("a".to_string()).code(),
+            "state.not_found"
+        );
+        assert_eq!(
+            StateError::NotFound("something else".to_string()).code(),
+            "state.not_found"
+        );
+        assert_eq!(
+            TransitionError::NotFound("a".to_string()).code(),
+            "transition.not_found"
+        );
+        assert_eq!(
+            DependencyError::CycleDetected(vec!["a".to_string(), "b".to_string()]).code(),
+            "dep.cycle"
+        );
+        assert_eq!(DependencyError::CycleDetected(vec![]).code(), "dep.cycle");
+    }
+
     #[test]
     fn test_validation_error_display() {
         let err = ValidationError::MissingField("name".to_string());
         assert_eq!(err.to_string(), "Missing required field: name");
     }
+
+    #[test]
+    fn test_validation_errors_accumulate() {
+        let mut errors = ValidationErrors::new();
+        errors.push(ValidationError::MissingField("name".to_string()));
+        errors.push(ValidationError::InvalidFormat {
+            field: "date".to_string(),
+            reason: "expected YYYY-MM-DD".to_string(),
+        });
+
+        assert!(!errors.is_empty());
+        let err = errors.into_result(()).unwrap_err();
+        assert_eq!(err.len(), 2);
+        assert_eq!(
+            err.to_string(),
+            "Missing required field: name\nInvalid format for date: expected YYYY-MM-DD"
+        );
+    }
+
+    #[test]
+    fn test_validation_errors_empty_is_ok() {
+        let errors = ValidationErrors::new();
+        assert!(errors.is_empty());
+        assert_eq!(errors.into_result(42).unwrap(), 42);
+    }
 }
@@ -4,7 +4,12 @@
 //! functions to provide type-safe argument parsing with clear error messages.
 
 use anyhow::{Context, Result};
-use crate::{get_kw_str, get_kw_value, parse_str_list, parse_value, require_kw_str};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use crate::{
+    get_kw_f64, get_kw_str, get_kw_value, iter_list, list_kw_keys, parse_str_list, parse_value,
+    require_kw_str,
+};
 
 /// Parse a tool call S-expression into a lexpr::Value.
 ///
@@ -20,6 +25,39 @@ pub fn parse_tool_call(sexpr: &str) -> Result<lexpr::Value> {
     parse_value(sexpr).context("failed to parse tool call s-expression")
 }
 
+/// Check that a tool call parses and starts with `expected_head`, without running it.
+///
+/// Useful for pre-validating requests before dispatch, and in tests.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::extract::validate_tool_call;
+///
+/// validate_tool_call("(my-tool :arg \"value\")", "my-tool")?;
+/// let err = validate_tool_call("(other-tool)", "my-tool").unwrap_err();
+/// assert!(err.to_string().contains("expected head my-tool"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn validate_tool_call(sexpr: &str, expected_head: &str) -> Result<()> {
+    let value = parse_tool_call(sexpr)?;
+    let list = value
+        .as_cons()
+        .ok_or_else(|| anyhow::anyhow!("tool call must be a list form, got: {}", value))?;
+    match list.car().as_symbol() {
+        Some(head) if head == expected_head => Ok(()),
+        Some(head) => Err(anyhow::anyhow!(
+            "tool call has head {}, expected head {}",
+            head,
+            expected_head
+        )),
+        None => Err(anyhow::anyhow!(
+            "tool call head must be a symbol, got: {}",
+            list.car()
+        )),
+    }
+}
+
 /// Extract a required string keyword argument.
 ///
 /// Returns an error if the keyword is missing.
@@ -56,6 +94,103 @@ pub fn get_string(value: &lexpr::Value, key: &str) -> Result<Option<String>> {
     get_kw_str(value, key).with_context(|| format!("Error extracting keyword :{}", key))
 }
 
+/// Extract an optional single-character keyword argument, such as `:sep ","`.
+///
+/// Returns an error stating the length found when the string is empty or has
+/// more than one character.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::extract::*;
+///
+/// let value = parse_tool_call("(tool :sep \",\")")?;
+/// assert_eq!(get_char(&value, "sep")?, Some(','));
+/// assert_eq!(get_char(&value, "missing")?, None);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn get_char(value: &lexpr::Value, key: &str) -> Result<Option<char>> {
+    let Some(s) = get_string(value, key)? else {
+        return Ok(None);
+    };
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(Some(c)),
+        _ => Err(anyhow::anyhow!(
+            ":{} must be a single character, got {} characters: {:?}",
+            key,
+            s.chars().count(),
+            s
+        )),
+    }
+}
+
+/// Extract an optional path keyword argument such as `:path "src/main.rs"`.
+///
+/// Empty strings are rejected, and `.` components and repeated separators are
+/// dropped, so `"./src//main.rs"` becomes `src/main.rs`. Use
+/// [`get_path_with`] to also reject `..` components and absolute paths.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::extract::*;
+/// use std::path::PathBuf;
+///
+/// let value = parse_tool_call("(tool :path \"./src//main.rs\")")?;
+/// assert_eq!(get_path(&value, "path")?, Some(PathBuf::from("src/main.rs")));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn get_path(value: &lexpr::Value, key: &str) -> Result<Option<PathBuf>> {
+    get_path_with(value, key, false)
+}
+
+/// Extract an optional path like [`get_path`], optionally confining it to a base directory.
+///
+/// With `forbid_parent` set, any `..` component is an error, and so is an
+/// absolute path (a root or, on Windows, a drive prefix), since joining one
+/// onto a base replaces the base. This keeps a sandboxed tool from climbing
+/// out of its base directory; combine it with
+/// [`resolve_text_ref`](crate::resolve_text_ref)-style joining onto a base.
+pub fn get_path_with(
+    value: &lexpr::Value,
+    key: &str,
+    forbid_parent: bool,
+) -> Result<Option<PathBuf>> {
+    let Some(s) = get_string(value, key)? else {
+        return Ok(None);
+    };
+    if s.is_empty() {
+        return Err(anyhow::anyhow!(":{} must not be an empty path", key));
+    }
+
+    let mut path = PathBuf::new();
+    for component in Path::new(&s).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if forbid_parent => {
+                return Err(anyhow::anyhow!(
+                    ":{} must not contain '..' components, got: {:?}",
+                    key,
+                    s
+                ));
+            }
+            Component::RootDir | Component::Prefix(_) if forbid_parent => {
+                return Err(anyhow::anyhow!(
+                    ":{} must be a relative path, got: {:?}",
+                    key,
+                    s
+                ));
+            }
+            other => path.push(other),
+        }
+    }
+    if path.as_os_str().is_empty() {
+        path.push(".");
+    }
+    Ok(Some(path))
+}
+
 /// Extract a required keyword argument as raw lexpr::Value.
 ///
 /// # Example
@@ -73,6 +208,31 @@ pub fn require_value(value: &lexpr::Value, key: &str) -> Result<lexpr::Value> {
         .ok_or_else(|| anyhow::anyhow!("Missing required keyword :{}", key))
 }
 
+/// Extract a required keyword argument that must be a proper list.
+///
+/// `()` counts as an (empty) list. Errors if the keyword is missing, the
+/// value is a scalar, or the list has a dotted tail.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::extract::*;
+///
+/// let value = parse_tool_call("(tool :items (1 2 3) :name \"x\")")?;
+/// assert!(require_list(&value, "items")?.as_cons().is_some());
+/// assert!(require_list(&value, "name").is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn require_list(value: &lexpr::Value, key: &str) -> Result<lexpr::Value> {
+    let list = require_value(value, key)?;
+    if list.as_cons().is_none() && !list.is_null() {
+        return Err(anyhow::anyhow!(":{} must be a list", key));
+    }
+    // Only the dotted-tail check matters here, not the items
+    let _ = iter_list(&list).with_context(|| format!(":{} must be a proper list", key))?;
+    Ok(list)
+}
+
 /// Extract an optional keyword argument as raw lexpr::Value.
 ///
 /// # Example
@@ -106,6 +266,45 @@ pub fn extract_string_list(value: &lexpr::Value) -> Result<Vec<String>> {
     parse_str_list(value).context("Failed to parse string list")
 }
 
+/// Extract an optional string list keyword argument.
+///
+/// Returns `Ok(None)` if the keyword is not present.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::extract::*;
+///
+/// let value = parse_tool_call("(tool :tags (\"a\" \"b\"))")?;
+/// assert_eq!(get_string_list(&value, "tags")?, Some(vec!["a".to_string(), "b".to_string()]));
+/// assert_eq!(get_string_list(&value, "missing")?, None);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn get_string_list(value: &lexpr::Value, key: &str) -> Result<Option<Vec<String>>> {
+    match get_kw_value(value, key)? {
+        None => Ok(None),
+        Some(v) => parse_str_list(&v)
+            .map(Some)
+            .with_context(|| format!(":{} must be a list of strings", key)),
+    }
+}
+
+/// Extract a required string list keyword argument.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::extract::*;
+///
+/// let value = parse_tool_call("(tool :tags (\"a\"))")?;
+/// assert_eq!(require_string_list(&value, "tags")?, vec!["a"]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn require_string_list(value: &lexpr::Value, key: &str) -> Result<Vec<String>> {
+    let list = require_list(value, key)?;
+    parse_str_list(&list).with_context(|| format!(":{} must be a list of strings", key))
+}
+
 /// Extract an optional boolean keyword argument.
 ///
 /// Accepts: `true`, `false`, `#t`, `#f`, `"true"`, `"false"`.
@@ -205,6 +404,206 @@ pub fn get_uint(value: &lexpr::Value, key: &str) -> Result<Option<usize>> {
     }
 }
 
+/// Extract an optional enumerated keyword argument.
+///
+/// Reads the keyword as a symbol or string and maps it with `parser`. When the
+/// parser returns `None`, the error lists the `accepted` names.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::extract::*;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Mode { Fast, Careful }
+///
+/// let value = parse_tool_call("(tool :mode fast)")?;
+/// let mode = get_enum(&value, "mode", &["fast", "careful"], |s| match s {
+///     "fast" => Some(Mode::Fast),
+///     "careful" => Some(Mode::Careful),
+///     _ => None,
+/// })?;
+/// assert_eq!(mode, Some(Mode::Fast));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn get_enum<T>(
+    value: &lexpr::Value,
+    key: &str,
+    accepted: &[&str],
+    parser: impl Fn(&str) -> Option<T>,
+) -> Result<Option<T>> {
+    match get_kw_value(value, key)? {
+        None => Ok(None),
+        Some(v) => {
+            let name = v.as_symbol().or_else(|| v.as_str()).ok_or_else(|| {
+                anyhow::anyhow!(":{} must be a symbol or string, got: {:?}", key, v)
+            })?;
+            parser(name).map(Some).ok_or_else(|| {
+                anyhow::anyhow!(
+                    ":{} must be one of {}, got: {}",
+                    key,
+                    accepted.join(", "),
+                    name
+                )
+            })
+        }
+    }
+}
+
+/// Extract an optional floating-point keyword argument.
+///
+/// Accepts floats, integers, and numeric strings; rejects `NaN` and infinities.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::extract::*;
+///
+/// let value = parse_tool_call("(tool :threshold 0.75)")?;
+/// assert_eq!(get_f64(&value, "threshold")?, Some(0.75));
+/// assert_eq!(get_f64(&value, "missing")?, None);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn get_f64(value: &lexpr::Value, key: &str) -> Result<Option<f64>> {
+    get_kw_f64(value, key).with_context(|| format!("Error extracting keyword :{}", key))
+}
+
+/// Extract a required floating-point keyword argument.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::extract::*;
+///
+/// let value = parse_tool_call("(tool :threshold 3)")?;
+/// assert_eq!(require_f64(&value, "threshold")?, 3.0);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn require_f64(value: &lexpr::Value, key: &str) -> Result<f64> {
+    get_f64(value, key)?.ok_or_else(|| anyhow::anyhow!("Missing required keyword :{}", key))
+}
+
+/// Extract an optional duration keyword argument such as `:timeout "10s"`.
+///
+/// Accepts a whole number followed by `ms`, `s`, `m` or `h`. A number with
+/// no unit, either as a string or a bare integer, is taken as seconds.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::extract::*;
+/// use std::time::Duration;
+///
+/// let value = parse_tool_call("(tool :timeout \"500ms\" :retry-after 5)")?;
+/// assert_eq!(get_duration(&value, "timeout")?, Some(Duration::from_millis(500)));
+/// assert_eq!(get_duration(&value, "retry-after")?, Some(Duration::from_secs(5)));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn get_duration(value: &lexpr::Value, key: &str) -> Result<Option<Duration>> {
+    let Some(v) = get_kw_value(value, key)? else {
+        return Ok(None);
+    };
+    if let Some(secs) = v.as_u64() {
+        return Ok(Some(Duration::from_secs(secs)));
+    }
+    let s = v
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!(":{} must be a duration like \"10s\", got: {:?}", key, v))?;
+    parse_duration(s)
+        .map(Some)
+        .with_context(|| format!("Invalid duration for :{}", key))
+}
+
+fn parse_duration(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let amount: u64 = number.parse().map_err(|_| {
+        anyhow::anyhow!("expected a whole number with an optional unit, got {:?}", s)
+    })?;
+    let multiplier_ms: u64 = match unit.trim() {
+        "ms" => 1,
+        "" | "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        other => {
+            return Err(anyhow::anyhow!(
+                "unknown duration unit {:?} (expected ms, s, m or h)",
+                other
+            ))
+        }
+    };
+    amount
+        .checked_mul(multiplier_ms)
+        .map(Duration::from_millis)
+        .ok_or_else(|| anyhow::anyhow!("duration {:?} is too large", s))
+}
+
+/// Check that a tool call only uses keywords from `allowed`.
+///
+/// Returns an error naming the first unexpected keyword, with a
+/// "did you mean" suggestion when an allowed keyword is within a small
+/// edit distance. Missing keywords are not checked here; use the
+/// `require_*` functions for that.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::extract::*;
+///
+/// let value = parse_tool_call("(tool :nmae \"x\")")?;
+/// let err = validate_kw_allowed(&value, &["name", "count"]).unwrap_err();
+/// assert!(err.to_string().contains("did you mean :name"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn validate_kw_allowed(value: &lexpr::Value, allowed: &[&str]) -> Result<()> {
+    for key in list_kw_keys(value)? {
+        if allowed.contains(&key.as_str()) {
+            continue;
+        }
+
+        let suggestion = allowed
+            .iter()
+            .map(|candidate| (edit_distance(&key, candidate), candidate))
+            .filter(|(distance, _)| *distance <= 2)
+            .min_by_key(|(distance, _)| *distance);
+
+        return Err(match suggestion {
+            Some((_, candidate)) => {
+                anyhow::anyhow!("Unexpected keyword :{} (did you mean :{}?)", key, candidate)
+            }
+            None => anyhow::anyhow!(
+                "Unexpected keyword :{} (allowed: {})",
+                key,
+                allowed
+                    .iter()
+                    .map(|k| format!(":{}", k))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Levenshtein distance between two strings, counted in chars.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        cur[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    prev[b.len()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -215,6 +614,25 @@ mod tests {
         assert!(value.as_cons().is_some());
     }
 
+    #[test]
+    fn test_validate_tool_call() {
+        assert!(validate_tool_call("(my-tool :arg \"value\")", "my-tool").is_ok());
+
+        let err = validate_tool_call("(other-tool :arg 1)", "my-tool").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "tool call has head other-tool, expected head my-tool"
+        );
+
+        let err = validate_tool_call("\"just a string\"", "my-tool").unwrap_err();
+        assert!(err.to_string().contains("must be a list form"));
+
+        let err = validate_tool_call("(\"tool\" :arg 1)", "tool").unwrap_err();
+        assert!(err.to_string().contains("head must be a symbol"));
+
+        assert!(validate_tool_call("(my-tool", "my-tool").is_err());
+    }
+
     #[test]
     fn test_require_string() {
         let value = parse_tool_call("(tool :name \"test\")").unwrap();
@@ -229,6 +647,57 @@ mod tests {
         assert_eq!(get_string(&value, "missing").unwrap(), None);
     }
 
+    #[test]
+    fn test_get_char() {
+        let value =
+            parse_tool_call("(tool :sep \",\" :arrow \"→\" :empty \"\" :two \"ab\")").unwrap();
+        assert_eq!(get_char(&value, "sep").unwrap(), Some(','));
+        assert_eq!(get_char(&value, "arrow").unwrap(), Some('→'));
+        assert_eq!(get_char(&value, "missing").unwrap(), None);
+
+        let err = get_char(&value, "empty").unwrap_err();
+        assert!(err.to_string().contains("got 0 characters"));
+
+        let err = get_char(&value, "two").unwrap_err();
+        assert!(err.to_string().contains("got 2 characters"));
+    }
+
+    #[test]
+    fn test_get_path() {
+        let value = parse_tool_call(
+            "(tool :a \"docs/./spec.md\" :b \"\" :c \"../secret\" :d \"a/../b\" :e \".\")",
+        )
+        .unwrap();
+        assert_eq!(
+            get_path(&value, "a").unwrap(),
+            Some(PathBuf::from("docs/spec.md"))
+        );
+        assert_eq!(get_path(&value, "e").unwrap(), Some(PathBuf::from(".")));
+        assert_eq!(get_path(&value, "missing").unwrap(), None);
+
+        let err = get_path(&value, "b").unwrap_err();
+        assert!(err.to_string().contains("empty path"));
+
+        // Traversal is only rejected when asked for
+        assert_eq!(
+            get_path(&value, "c").unwrap(),
+            Some(PathBuf::from("../secret"))
+        );
+        let err = get_path_with(&value, "c", true).unwrap_err();
+        assert!(err.to_string().contains("'..'"));
+        assert!(get_path_with(&value, "d", true).is_err());
+        assert!(get_path_with(&value, "a", true).is_ok());
+
+        // Absolute paths would replace the base directory when joined onto it
+        let value = parse_tool_call("(tool :path \"/etc/passwd\")").unwrap();
+        assert_eq!(
+            get_path(&value, "path").unwrap(),
+            Some(PathBuf::from("/etc/passwd"))
+        );
+        let err = get_path_with(&value, "path", true).unwrap_err();
+        assert!(err.to_string().contains("relative path"));
+    }
+
     #[test]
     fn test_get_bool() {
         let value = parse_tool_call("(tool :enabled true :disabled false)").unwrap();
@@ -250,6 +719,153 @@ mod tests {
         assert_eq!(get_uint(&value, "limit").unwrap(), Some(100));
     }
 
+    #[test]
+    fn test_get_f64() {
+        let value = parse_tool_call("(tool :a 0.75 :b 3 :c \"1.5\" :d fast)").unwrap();
+        assert_eq!(get_f64(&value, "a").unwrap(), Some(0.75));
+        assert_eq!(get_f64(&value, "b").unwrap(), Some(3.0));
+        assert_eq!(require_f64(&value, "c").unwrap(), 1.5);
+        assert!(get_f64(&value, "d").is_err());
+        assert!(require_f64(&value, "missing").is_err());
+    }
+
+    #[test]
+    fn test_get_duration() {
+        let value = parse_tool_call(
+            "(tool :a \"10s\" :b \"500ms\" :c \"5\" :d \"10x\" :e 3 :f \"2m\" :g \"1h\" :h \"ms\")",
+        )
+        .unwrap();
+        assert_eq!(
+            get_duration(&value, "a").unwrap(),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            get_duration(&value, "b").unwrap(),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(
+            get_duration(&value, "c").unwrap(),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(
+            get_duration(&value, "e").unwrap(),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(
+            get_duration(&value, "f").unwrap(),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            get_duration(&value, "g").unwrap(),
+            Some(Duration::from_secs(3600))
+        );
+        assert_eq!(get_duration(&value, "missing").unwrap(), None);
+
+        let err = get_duration(&value, "d").unwrap_err();
+        assert!(format!("{:#}", err).contains("unknown duration unit \"x\""));
+        assert!(get_duration(&value, "h").is_err());
+    }
+
+    #[test]
+    fn test_get_string_list() {
+        let value = parse_tool_call("(tool :tags (\"a\" \"b\") :bad (\"a\" 1))").unwrap();
+        assert_eq!(
+            get_string_list(&value, "tags").unwrap(),
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(get_string_list(&value, "missing").unwrap(), None);
+        assert!(require_string_list(&value, "missing").is_err());
+
+        let err = get_string_list(&value, "bad").unwrap_err();
+        assert!(err.to_string().contains(":bad"));
+    }
+
+    #[test]
+    fn test_require_list() {
+        let value = parse_tool_call(
+            "(tool :items (\"a\" \"b\") :empty () :name \"x\" :dotted (\"a\" . \"b\"))",
+        )
+        .unwrap();
+        let items = require_list(&value, "items").unwrap();
+        assert_eq!(parse_str_list(&items).unwrap(), vec!["a", "b"]);
+        assert!(require_list(&value, "empty").unwrap().is_null());
+        assert_eq!(
+            require_string_list(&value, "items").unwrap(),
+            vec!["a", "b"]
+        );
+
+        let err = require_list(&value, "name").unwrap_err();
+        assert!(err.to_string().contains(":name must be a list"));
+        let err = require_list(&value, "dotted").unwrap_err();
+        assert!(err.to_string().contains(":dotted must be a proper list"));
+        assert!(require_string_list(&value, "dotted").is_err());
+        assert!(require_list(&value, "missing").is_err());
+    }
+
+    #[test]
+    fn test_get_enum() {
+        #[derive(Debug, PartialEq)]
+        enum Mode {
+            Fast,
+            Careful,
+        }
+
+        fn parse_mode(s: &str) -> Option<Mode> {
+            match s {
+                "fast" => Some(Mode::Fast),
+                "careful" => Some(Mode::Careful),
+                _ => None,
+            }
+        }
+
+        let accepted = ["fast", "careful"];
+        let value = parse_tool_call("(tool :a fast :b \"careful\" :c reckless)").unwrap();
+        assert_eq!(
+            get_enum(&value, "a", &accepted, parse_mode).unwrap(),
+            Some(Mode::Fast)
+        );
+        assert_eq!(
+            get_enum(&value, "b", &accepted, parse_mode).unwrap(),
+            Some(Mode::Careful)
+        );
+        assert_eq!(
+            get_enum(&value, "missing", &accepted, parse_mode).unwrap(),
+            None
+        );
+
+        let err = get_enum(&value, "c", &accepted, parse_mode).unwrap_err();
+        assert!(err.to_string().contains("fast, careful"));
+    }
+
+    #[test]
+    fn test_validate_kw_allowed() {
+        let allowed = ["name", "count"];
+
+        let value = parse_tool_call("(tool :name \"x\" :count 1)").unwrap();
+        assert!(validate_kw_allowed(&value, &allowed).is_ok());
+
+        let value = parse_tool_call("(tool :name \"x\" :verbose #t)").unwrap();
+        let err = validate_kw_allowed(&value, &allowed)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(":verbose"));
+        assert!(!err.contains("did you mean"));
+
+        let value = parse_tool_call("(tool :nmae \"x\")").unwrap();
+        let err = validate_kw_allowed(&value, &allowed)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("did you mean :name?"));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("name", "name"), 0);
+        assert_eq!(edit_distance("nmae", "name"), 2);
+        assert_eq!(edit_distance("count", "cont"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
     #[test]
     fn test_extract_string_list() {
         let value = parse_tool_call("(tool :items (\"a\" \"b\" \"c\"))").unwrap();
@@ -0,0 +1,271 @@
+//! Serde deserialization of tool-call keyword arguments.
+//!
+//! Maps the keyword section of a form like `(tool :name "x" :count 3)` onto
+//! the fields of a `Deserialize` type. Nested keyword lists such as
+//! `(:retries 3)` deserialize into nested structs, plain lists into sequences,
+//! and symbols or strings into unit enum variants.
+
+use crate::{is_explicit_kw, iter_list, kw_pairs as collect_kw_pairs};
+use anyhow::{anyhow, Result};
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use std::fmt;
+
+/// Deserialize the keyword arguments of a tool-call form into `T`.
+///
+/// Keyword names are matched against field names as written, so use
+/// `#[serde(rename_all = "kebab-case")]` for keys like `:max-count`.
+/// Missing required fields produce an error naming the field; unknown
+/// keywords are ignored unless `T` uses `#[serde(deny_unknown_fields)]`.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::extract::*;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Args {
+///     name: String,
+///     count: u32,
+/// }
+///
+/// let value = parse_tool_call("(tool :name \"x\" :count 3)")?;
+/// let args: Args = from_tool_call(&value)?;
+/// assert_eq!(args.name, "x");
+/// assert_eq!(args.count, 3);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn from_tool_call<T: DeserializeOwned>(value: &lexpr::Value) -> Result<T> {
+    let list = value
+        .as_cons()
+        .ok_or_else(|| anyhow!("expected list (tool call form)"))?;
+
+    let pairs = kw_pairs(list.cdr()).map_err(|e| anyhow!("{}", e))?;
+    let deserializer: MapDeserializer<_, DeError> = MapDeserializer::new(pairs.into_iter());
+    T::deserialize(deserializer).map_err(|e| anyhow!("failed to deserialize tool call: {}", e))
+}
+
+/// Types that can be built from the keyword arguments of a tool-call form.
+///
+/// Implemented for every `DeserializeOwned` type via [`from_tool_call`], so
+/// deriving `Deserialize` is enough. Implement it by hand for argument types
+/// that need custom parsing.
+pub trait FromToolCall: Sized {
+    /// Build `Self` from a parsed tool-call form.
+    fn from_tool_call(value: &lexpr::Value) -> Result<Self>;
+}
+
+impl<T: DeserializeOwned> FromToolCall for T {
+    fn from_tool_call(value: &lexpr::Value) -> Result<Self> {
+        from_tool_call(value)
+    }
+}
+
+#[derive(Debug)]
+struct DeError(String);
+
+impl fmt::Display for DeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for DeError {}
+
+impl de::Error for DeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        DeError(msg.to_string())
+    }
+}
+
+struct ValueDeserializer(lexpr::Value);
+
+impl<'de> IntoDeserializer<'de, DeError> for ValueDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+fn kw_pairs(start: &lexpr::Value) -> Result<Vec<(String, ValueDeserializer)>, DeError> {
+    let pairs = collect_kw_pairs(start).map_err(|e| DeError(e.to_string()))?;
+    Ok(pairs
+        .into_iter()
+        .map(|(key, value)| (key, ValueDeserializer(value)))
+        .collect())
+}
+
+fn list_items(value: &lexpr::Value) -> Result<Vec<ValueDeserializer>, DeError> {
+    let items = iter_list(value).map_err(|e| DeError(e.to_string()))?;
+    Ok(items.map(ValueDeserializer).collect())
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        let value = self.0;
+        match &value {
+            lexpr::Value::Nil => visitor.visit_unit(),
+            lexpr::Value::Null => {
+                visitor.visit_seq(SeqDeserializer::new(std::iter::empty::<ValueDeserializer>()))
+            }
+            lexpr::Value::Bool(b) => visitor.visit_bool(*b),
+            lexpr::Value::Number(_) => {
+                if let Some(n) = value.as_i64() {
+                    visitor.visit_i64(n)
+                } else if let Some(n) = value.as_u64() {
+                    visitor.visit_u64(n)
+                } else if let Some(n) = value.as_f64() {
+                    visitor.visit_f64(n)
+                } else {
+                    Err(de::Error::custom(format!("unsupported number: {}", value)))
+                }
+            }
+            lexpr::Value::String(s) | lexpr::Value::Symbol(s) => visitor.visit_str(s),
+            lexpr::Value::Keyword(k) => visitor.visit_str(k),
+            lexpr::Value::Cons(cons) => {
+                if is_explicit_kw(cons.car()) {
+                    visitor.visit_map(MapDeserializer::new(kw_pairs(&value)?.into_iter()))
+                } else {
+                    visitor.visit_seq(SeqDeserializer::new(list_items(&value)?.into_iter()))
+                }
+            }
+            other => Err(de::Error::custom(format!("unsupported value: {}", other))),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0 {
+            lexpr::Value::Nil => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        let variant = self
+            .0
+            .as_symbol()
+            .or_else(|| self.0.as_str())
+            .ok_or_else(|| DeError(format!("expected symbol or string, got: {}", self.0)))?;
+        let variant: de::value::StringDeserializer<DeError> =
+            variant.to_string().into_deserializer();
+        visitor.visit_enum(variant)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        // A headed form like `(config :retries 3)` maps its keyword section.
+        let headed = match self.0.as_cons() {
+            Some(cons) if cons.car().as_symbol().is_some() && !is_explicit_kw(cons.car()) => {
+                Some(kw_pairs(cons.cdr())?)
+            }
+            _ => None,
+        };
+
+        match headed {
+            Some(pairs) => visitor.visit_map(MapDeserializer::new(pairs.into_iter())),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_map(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::parse_tool_call;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "kebab-case")]
+    enum Mode {
+        Fast,
+        Careful,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    struct Retry {
+        max_attempts: u32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Args {
+        name: String,
+        count: u32,
+        #[serde(default)]
+        tags: Vec<String>,
+        mode: Option<Mode>,
+        retry: Option<Retry>,
+    }
+
+    #[test]
+    fn test_from_tool_call_basic() {
+        let value = parse_tool_call("(tool :name \"x\" :count 3 :extra #t)").unwrap();
+        let args: Args = from_tool_call(&value).unwrap();
+        assert_eq!(args.name, "x");
+        assert_eq!(args.count, 3);
+        assert!(args.tags.is_empty());
+        assert!(args.mode.is_none());
+    }
+
+    #[test]
+    fn test_from_tool_call_nested() {
+        let value = parse_tool_call(
+            "(tool :name \"x\" :count 1 :tags (\"a\" \"b\") :mode careful :retry (:max-attempts 5))",
+        )
+        .unwrap();
+        let args: Args = from_tool_call(&value).unwrap();
+        assert_eq!(args.tags, vec!["a", "b"]);
+        assert_eq!(args.mode, Some(Mode::Careful));
+        assert_eq!(args.retry.unwrap().max_attempts, 5);
+    }
+
+    #[test]
+    fn test_from_tool_call_missing_field() {
+        let value = parse_tool_call("(tool :count 3)").unwrap();
+        let err = from_tool_call::<Args>(&value).unwrap_err();
+        assert!(err.to_string().contains("name"));
+    }
+
+    #[test]
+    fn test_from_tool_call_deny_unknown_fields() {
+        #[derive(Debug, Deserialize)]
+        #[serde(deny_unknown_fields)]
+        #[allow(dead_code)]
+        struct Strict {
+            name: String,
+        }
+
+        let value = parse_tool_call("(tool :name \"x\" :nmae \"y\")").unwrap();
+        assert!(from_tool_call::<Strict>(&value).is_err());
+    }
+}
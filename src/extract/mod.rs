@@ -19,5 +19,7 @@
 //! ```
 
 pub mod args;
+pub mod de;
 
 pub use args::*;
+pub use de::{from_tool_call, FromToolCall};
@@ -0,0 +1,222 @@
+//! Building S-expression forms as `lexpr::Value`s.
+//!
+//! Unlike [`ResponseBuilder`](super::ResponseBuilder), which concatenates
+//! strings, [`SexprForm`] produces a structured value that can be inspected,
+//! nested inside other forms, and rendered once at the end with
+//! [`render`] (or [`render_pretty`](super::render_pretty)).
+
+/// Render a `lexpr::Value` as a single-line S-expression.
+///
+/// Uses the same escaping as the string-based formatters, so a form built
+/// with [`SexprForm`] renders identically to its `format_*` counterpart.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::format::{format_success, render, SexprForm};
+///
+/// let mut form = SexprForm::head("success");
+/// form.kw("id", "abc");
+/// assert_eq!(render(&form.build()), format_success(&[("id", "abc")]));
+/// ```
+pub fn render(value: &lexpr::Value) -> String {
+    crate::render_value(value)
+}
+
+/// Conversion into a `lexpr::Value` for use with [`SexprForm`].
+///
+/// Strings become string literals, not symbols; pass
+/// `lexpr::Value::symbol(..)` to emit a bare symbol.
+pub trait IntoSexpr {
+    /// Convert `self` into a `lexpr::Value`.
+    fn into_sexpr(self) -> lexpr::Value;
+}
+
+impl IntoSexpr for lexpr::Value {
+    fn into_sexpr(self) -> lexpr::Value {
+        self
+    }
+}
+
+impl IntoSexpr for &str {
+    fn into_sexpr(self) -> lexpr::Value {
+        lexpr::Value::string(self)
+    }
+}
+
+impl IntoSexpr for String {
+    fn into_sexpr(self) -> lexpr::Value {
+        lexpr::Value::string(self)
+    }
+}
+
+impl IntoSexpr for bool {
+    fn into_sexpr(self) -> lexpr::Value {
+        lexpr::Value::Bool(self)
+    }
+}
+
+impl IntoSexpr for i64 {
+    fn into_sexpr(self) -> lexpr::Value {
+        lexpr::Value::from(self)
+    }
+}
+
+impl IntoSexpr for i32 {
+    fn into_sexpr(self) -> lexpr::Value {
+        lexpr::Value::from(i64::from(self))
+    }
+}
+
+impl IntoSexpr for u64 {
+    fn into_sexpr(self) -> lexpr::Value {
+        lexpr::Value::from(self)
+    }
+}
+
+impl IntoSexpr for f64 {
+    fn into_sexpr(self) -> lexpr::Value {
+        lexpr::Value::from(self)
+    }
+}
+
+impl IntoSexpr for SexprForm {
+    fn into_sexpr(self) -> lexpr::Value {
+        self.build()
+    }
+}
+
+impl<T: IntoSexpr> IntoSexpr for Vec<T> {
+    fn into_sexpr(self) -> lexpr::Value {
+        lexpr::Value::list(self.into_iter().map(IntoSexpr::into_sexpr))
+    }
+}
+
+/// Builder for `(head positional... :key value...)` forms.
+///
+/// Positional values always come right after the head, followed by the
+/// keyword arguments, each group in insertion order.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::format::SexprForm;
+/// use mcp_tools::render_value;
+///
+/// let mut inner = SexprForm::head("file");
+/// inner.positional("src/main.rs");
+///
+/// let mut form = SexprForm::head("success");
+/// form.kw("id", "abc")
+///     .kw("count", 3)
+///     .kw("tags", vec!["a", "b"])
+///     .kw("resource", inner);
+/// assert_eq!(
+///     render_value(&form.build()),
+///     "(success :id \"abc\" :count 3 :tags (\"a\" \"b\") :resource (file \"src/main.rs\"))"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct SexprForm {
+    head: String,
+    positionals: Vec<lexpr::Value>,
+    keywords: Vec<(String, lexpr::Value)>,
+}
+
+impl SexprForm {
+    /// Start a form with the given head symbol (e.g. `success`).
+    pub fn head(sym: &str) -> Self {
+        Self {
+            head: sym.to_string(),
+            positionals: Vec::new(),
+            keywords: Vec::new(),
+        }
+    }
+
+    /// Add a keyword argument `:key value`.
+    pub fn kw(&mut self, key: &str, value: impl IntoSexpr) -> &mut Self {
+        self.keywords.push((key.to_string(), value.into_sexpr()));
+        self
+    }
+
+    /// Add a positional value after the head.
+    pub fn positional(&mut self, value: impl IntoSexpr) -> &mut Self {
+        self.positionals.push(value.into_sexpr());
+        self
+    }
+
+    /// Build the form as a proper list.
+    pub fn build(&self) -> lexpr::Value {
+        let mut items = Vec::with_capacity(1 + self.positionals.len() + 2 * self.keywords.len());
+        items.push(lexpr::Value::symbol(self.head.as_str()));
+        items.extend(self.positionals.iter().cloned());
+        for (key, value) in &self.keywords {
+            items.push(lexpr::Value::keyword(key.as_str()));
+            items.push(value.clone());
+        }
+        lexpr::Value::list(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        get_kw_int, get_kw_path, get_kw_symbol, get_positional, parse_value, render_value,
+        require_kw_str,
+    };
+
+    #[test]
+    fn test_build_nested_form() {
+        let mut config = SexprForm::head("config");
+        config.kw("retries", 3).kw("verbose", false);
+
+        let mut form = SexprForm::head("resize");
+        form.positional(800)
+            .kw("mode", lexpr::Value::symbol("fit"))
+            .positional(600)
+            .kw("label", "say \"hi\"")
+            .kw("config", config)
+            .kw("sizes", vec![1.5, 2.5]);
+
+        let rendered = render_value(&form.build());
+        assert_eq!(
+            rendered,
+            "(resize 800 600 :mode fit :label \"say \\\"hi\\\"\" :config (config :retries 3 :verbose #f) :sizes (1.5 2.5))"
+        );
+
+        let parsed = parse_value(&rendered).unwrap();
+        assert_eq!(
+            get_positional(&parsed, 1).unwrap().unwrap().as_i64(),
+            Some(600)
+        );
+        assert_eq!(
+            get_kw_symbol(&parsed, "mode").unwrap().as_deref(),
+            Some("fit")
+        );
+        let retries = get_kw_path(&parsed, &["config", "retries"]).unwrap();
+        assert_eq!(retries.and_then(|v| v.as_i64()), Some(3));
+        assert_eq!(require_kw_str(&parsed, "label").unwrap(), "say \"hi\"");
+    }
+
+    #[test]
+    fn test_render_matches_format_success() {
+        let mut form = SexprForm::head("success");
+        form.kw("internal-id", "uuid-123")
+            .kw("message", "say \"hi\"\nbye");
+        assert_eq!(
+            render(&form.build()),
+            crate::format::format_success(&[
+                ("internal-id", "uuid-123"),
+                ("message", "say \"hi\"\nbye"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_build_head_only() {
+        let form = SexprForm::head("complete").build();
+        assert_eq!(render_value(&form), "(complete)");
+        assert_eq!(get_kw_int(&form, "missing").unwrap(), None);
+    }
+}
@@ -21,6 +21,10 @@
 //! // => "(error \"Resource not found\")"
 //! ```
 
+pub mod form;
+pub mod pretty;
 pub mod response;
 
+pub use form::*;
+pub use pretty::*;
 pub use response::*;
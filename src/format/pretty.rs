@@ -0,0 +1,118 @@
+//! Multi-line pretty-printing of S-expression responses.
+//!
+//! Built on the root `render_value()` so atoms, and in particular strings,
+//! are escaped exactly as in single-line output.
+
+use crate::{is_explicit_kw, iter_list, parse_value, render_value};
+use anyhow::Result;
+
+/// Re-render an S-expression across multiple lines.
+///
+/// Each list element goes on its own line, indented by `indent` spaces per
+/// nesting level, with keyword/value pairs kept together. Lists made only of
+/// non-keyword atoms, such as `("a" "b")`, stay on a single line.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::format::format_pretty;
+///
+/// let pretty = format_pretty("(blocked :waiting-goals (\"g1\" \"g2\") :msg \"w\")", 2)?;
+/// assert_eq!(pretty, "(blocked\n  :waiting-goals (\"g1\" \"g2\")\n  :msg \"w\")");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn format_pretty(sexpr: &str, indent: usize) -> Result<String> {
+    let value = parse_value(sexpr)?;
+    Ok(render_pretty(&value, indent))
+}
+
+/// Render a `lexpr::Value` across multiple lines.
+///
+/// The value-based counterpart of [`format_pretty`], for forms built with
+/// [`SexprForm`](super::SexprForm) that have not been rendered yet.
+pub fn render_pretty(value: &lexpr::Value, indent: usize) -> String {
+    let mut out = String::new();
+    write_pretty(value, indent, 0, &mut out);
+    out
+}
+
+fn is_inline(value: &lexpr::Value) -> bool {
+    match iter_list(value) {
+        Ok(mut items) => items.all(|item| item.as_cons().is_none() && !is_explicit_kw(&item)),
+        Err(_) => true,
+    }
+}
+
+fn write_pretty(value: &lexpr::Value, indent: usize, depth: usize, out: &mut String) {
+    if value.as_cons().is_none() || is_inline(value) {
+        out.push_str(&render_value(value));
+        return;
+    }
+
+    // Dotted lists are rendered inline above, so this cannot fail.
+    let items: Vec<lexpr::Value> = iter_list(value)
+        .map(|items| items.collect())
+        .unwrap_or_default();
+    let pad = " ".repeat(indent * (depth + 1));
+
+    out.push('(');
+    let mut i = 0;
+    while i < items.len() {
+        if i > 0 {
+            out.push('\n');
+            out.push_str(&pad);
+        }
+
+        if is_explicit_kw(&items[i]) && i + 1 < items.len() {
+            out.push_str(&render_value(&items[i]));
+            out.push(' ');
+            write_pretty(&items[i + 1], indent, depth + 1, out);
+            i += 2;
+        } else {
+            write_pretty(&items[i], indent, depth + 1, out);
+            i += 1;
+        }
+    }
+    out.push(')');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_pretty_flat() {
+        let result = format_pretty("(success :id \"123\" :status \"ok\")", 2).unwrap();
+        assert_eq!(result, "(success\n  :id \"123\"\n  :status \"ok\")");
+    }
+
+    #[test]
+    fn test_format_pretty_nested() {
+        let input = "(blocked :waiting-goals (\"g1\" \"g2\") :detail (info :a 1 :b (x :c \"y\")))";
+        let result = format_pretty(input, 2).unwrap();
+        assert_eq!(
+            result,
+            "(blocked\n  :waiting-goals (\"g1\" \"g2\")\n  :detail (info\n    :a 1\n    :b (x\n      :c \"y\")))"
+        );
+    }
+
+    #[test]
+    fn test_render_pretty_matches_format_pretty() {
+        let input = "(success :id \"123\" :tags (\"a\" \"b\"))";
+        let value = parse_value(input).unwrap();
+        assert_eq!(render_pretty(&value, 2), format_pretty(input, 2).unwrap());
+    }
+
+    #[test]
+    fn test_format_pretty_round_trip() {
+        let inputs = [
+            "(success :message \"line\\nbreak \\\"quoted\\\"\")",
+            "(a (b (c d) e) :k (1 2 3) (x . y))",
+            "atom",
+        ];
+        for input in inputs {
+            let pretty = format_pretty(input, 4).unwrap();
+            assert_eq!(parse_value(&pretty).unwrap(), parse_value(input).unwrap());
+        }
+    }
+}
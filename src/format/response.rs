@@ -19,9 +19,61 @@ use crate::{quote_str, render_list};
 /// assert_eq!(response, "(success :internal-id \"uuid-123\" :status \"complete\")");
 /// ```
 pub fn format_success(fields: &[(&str, &str)]) -> String {
+    let values: Vec<(&str, FieldValue)> = fields
+        .iter()
+        .map(|(key, value)| (*key, FieldValue::Str(value)))
+        .collect();
+    format_success_values(&values)
+}
+
+/// A typed field value for response formatting.
+///
+/// Only `Str` values are quoted; numbers and booleans are emitted as bare
+/// atoms so `:count 3` stays distinct from `:count "3"`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue<'a> {
+    /// A string, rendered with `quote_str()`.
+    Str(&'a str),
+    /// An integer, rendered unquoted.
+    Int(i64),
+    /// A boolean, rendered as `#t` or `#f`.
+    Bool(bool),
+    /// A pre-rendered S-expression fragment, emitted verbatim.
+    Raw(String),
+}
+
+impl FieldValue<'_> {
+    /// Render this value as an S-expression fragment.
+    pub fn render(&self) -> String {
+        match self {
+            FieldValue::Str(s) => quote_str(s),
+            FieldValue::Int(n) => n.to_string(),
+            FieldValue::Bool(true) => "#t".to_string(),
+            FieldValue::Bool(false) => "#f".to_string(),
+            FieldValue::Raw(raw) => raw.clone(),
+        }
+    }
+}
+
+/// Format a success response with typed field values.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::format::{format_success_values, FieldValue};
+///
+/// let response = format_success_values(&[
+///     ("name", FieldValue::Str("x")),
+///     ("count", FieldValue::Int(3)),
+///     ("done", FieldValue::Bool(true)),
+///     ("items", FieldValue::Raw("(1 2)".to_string())),
+/// ]);
+/// assert_eq!(response, "(success :name \"x\" :count 3 :done #t :items (1 2))");
+/// ```
+pub fn format_success_values(fields: &[(&str, FieldValue)]) -> String {
     let field_strs: Vec<String> = fields
         .iter()
-        .map(|(key, value)| format!(":{} {}", key, quote_str(value)))
+        .map(|(key, value)| format!(":{} {}", key, value.render()))
         .collect();
     format!("(success {})", field_strs.join(" "))
 }
@@ -40,6 +92,32 @@ pub fn format_error(message: &str) -> String {
     format!("(error {})", quote_str(message))
 }
 
+/// Format an error response with a machine-readable code.
+///
+/// Clients can branch on `:code` instead of matching message text. The typed
+/// errors in the `errors` module expose such codes via their `code()`
+/// methods, so `format_error_coded(err.code(), &err.to_string())` renders
+/// one directly.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::format::format_error_coded;
+///
+/// let response = format_error_coded("state.not_found", "Resource not found: a");
+/// assert_eq!(
+///     response,
+///     "(error :code \"state.not_found\" :message \"Resource not found: a\")"
+/// );
+/// ```
+pub fn format_error_coded(code: &str, message: &str) -> String {
+    format!(
+        "(error :code {} :message {})",
+        quote_str(code),
+        quote_str(message)
+    )
+}
+
 /// Format a complete response with optional fields.
 ///
 /// # Example
@@ -96,6 +174,54 @@ pub fn format_blocked(waiting_goals: &[String], fields: &[(&str, &str)]) -> Stri
     }
 }
 
+/// Format a progress event for streaming responses.
+///
+/// `percent` is clamped to `0..=100`.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::format::format_progress;
+///
+/// let response = format_progress(40, "indexing", &[("stage", "scan")]);
+/// assert_eq!(response, "(progress :percent 40 :message \"indexing\" :stage \"scan\")");
+/// ```
+pub fn format_progress(percent: u8, message: &str, extra: &[(&str, &str)]) -> String {
+    let mut out = format!(
+        "(progress :percent {} :message {}",
+        percent.min(100),
+        quote_str(message)
+    );
+    for (key, value) in extra {
+        out.push_str(&format!(" :{} {}", key, quote_str(value)));
+    }
+    out.push(')');
+    out
+}
+
+/// Format a progress snapshot as `(snapshot :event "..." ...)`.
+///
+/// The result is meant to be stored as the `snapshot_text` of a
+/// `persistence::ProgressSnapshot`, alongside the same `event`, so snapshots
+/// written by different servers share one canonical shape.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::format::format_snapshot;
+///
+/// let text = format_snapshot("step-done", &[("step", "3"), ("goal", "build")]);
+/// assert_eq!(text, "(snapshot :event \"step-done\" :step \"3\" :goal \"build\")");
+/// ```
+pub fn format_snapshot(event: &str, fields: &[(&str, &str)]) -> String {
+    let mut out = format!("(snapshot :event {}", quote_str(event));
+    for (key, value) in fields {
+        out.push_str(&format!(" :{} {}", key, quote_str(value)));
+    }
+    out.push(')');
+    out
+}
+
 /// Serialize a list of strings as space-separated quoted strings.
 ///
 /// This wraps the existing `render_list()` function with automatic quoting.
@@ -127,6 +253,75 @@ pub fn serialize_resource(resource_type: &str, value: &str) -> String {
     format!("({} {})", resource_type, quote_str(value))
 }
 
+/// Incremental builder for `(head :key value ...)` responses.
+///
+/// Useful when fields are computed conditionally or in a loop. String values
+/// are quoted with `quote_str()`; integers and booleans are emitted as bare
+/// atoms.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::format::ResponseBuilder;
+///
+/// let mut builder = ResponseBuilder::new("success");
+/// builder.field("id", "abc").field_int("count", 3);
+/// for flag in ["dry-run"] {
+///     builder.field_bool(flag, true);
+/// }
+/// assert_eq!(builder.build(), "(success :id \"abc\" :count 3 :dry-run #t)");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ResponseBuilder {
+    head: String,
+    fields: Vec<String>,
+}
+
+impl ResponseBuilder {
+    /// Start a response with the given head symbol (e.g. `success`).
+    pub fn new(head: &str) -> Self {
+        Self {
+            head: head.to_string(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Add a quoted string field.
+    pub fn field(&mut self, key: &str, value: &str) -> &mut Self {
+        self.fields.push(format!(":{} {}", key, quote_str(value)));
+        self
+    }
+
+    /// Add an integer field.
+    pub fn field_int(&mut self, key: &str, value: i64) -> &mut Self {
+        self.fields.push(format!(":{} {}", key, value));
+        self
+    }
+
+    /// Add a boolean field, rendered as `#t` or `#f`.
+    pub fn field_bool(&mut self, key: &str, value: bool) -> &mut Self {
+        let atom = if value { "#t" } else { "#f" };
+        self.fields.push(format!(":{} {}", key, atom));
+        self
+    }
+
+    /// Add a field whose value is a list of quoted strings.
+    pub fn list_field(&mut self, key: &str, items: &[String]) -> &mut Self {
+        self.fields
+            .push(format!(":{} ({})", key, serialize_string_list(items)));
+        self
+    }
+
+    /// Render the response.
+    pub fn build(&self) -> String {
+        if self.fields.is_empty() {
+            format!("({})", self.head)
+        } else {
+            format!("({} {})", self.head, self.fields.join(" "))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,12 +332,56 @@ mod tests {
         assert_eq!(result, "(success :id \"123\" :status \"ok\")");
     }
 
+    #[test]
+    fn test_format_success_values() {
+        let result = format_success_values(&[
+            ("count", FieldValue::Int(3)),
+            ("name", FieldValue::Str("x")),
+            ("ok", FieldValue::Bool(false)),
+        ]);
+        assert_eq!(result, "(success :count 3 :name \"x\" :ok #f)");
+
+        let quoted = format_success_values(&[("count", FieldValue::Str("3"))]);
+        assert_eq!(quoted, "(success :count \"3\")");
+    }
+
     #[test]
     fn test_format_error() {
         let result = format_error("Not found");
         assert_eq!(result, "(error \"Not found\")");
     }
 
+    #[test]
+    fn test_format_error_coded() {
+        use crate::{get_kw_str, get_positional, parse_value};
+
+        let coded = format_error_coded("io", "disk \"full\"");
+        assert_eq!(coded, "(error :code \"io\" :message \"disk \\\"full\\\"\")");
+        let parsed = parse_value(&coded).unwrap();
+        assert_eq!(get_kw_str(&parsed, "code").unwrap().as_deref(), Some("io"));
+        assert_eq!(
+            get_kw_str(&parsed, "message").unwrap().as_deref(),
+            Some("disk \"full\"")
+        );
+
+        let plain = parse_value(&format_error("disk \"full\"")).unwrap();
+        assert_eq!(get_kw_str(&plain, "code").unwrap(), None);
+        let message = get_positional(&plain, 0).unwrap().unwrap();
+        assert_eq!(message.as_str(), Some("disk \"full\""));
+    }
+
+    #[cfg(feature = "errors")]
+    #[test]
+    fn test_format_error_coded_from_typed_error() {
+        use crate::errors::StateError;
+
+        let err = StateError::NotFound("a".to_string());
+        assert_eq!(
+            format_error_coded(err.code(), &err.to_string()),
+            "(error :code \"state.not_found\" :message \"Resource not found: a\")"
+        );
+    }
+
     #[test]
     fn test_format_complete() {
         let result = format_complete(&[]);
@@ -162,6 +401,88 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_response_builder_matches_format_success() {
+        let fields = [("id", "123"), ("status", "ok")];
+        let mut builder = ResponseBuilder::new("success");
+        for (key, value) in fields {
+            builder.field(key, value);
+        }
+        assert_eq!(builder.build(), format_success(&fields));
+    }
+
+    #[test]
+    fn test_response_builder_typed_fields() {
+        let goals = vec!["g1".to_string(), "g\"2".to_string()];
+        let result = ResponseBuilder::new("blocked")
+            .list_field("waiting-goals", &goals)
+            .field_int("count", -2)
+            .field_bool("retry", false)
+            .build();
+        assert_eq!(
+            result,
+            "(blocked :waiting-goals (\"g1\" \"g\\\"2\") :count -2 :retry #f)"
+        );
+        assert_eq!(ResponseBuilder::new("complete").build(), "(complete)");
+    }
+
+    #[test]
+    fn test_format_progress() {
+        assert_eq!(
+            format_progress(40, "working", &[]),
+            "(progress :percent 40 :message \"working\")"
+        );
+        assert_eq!(
+            format_progress(75, "step", &[("stage", "2/3"), ("id", "x")]),
+            "(progress :percent 75 :message \"step\" :stage \"2/3\" :id \"x\")"
+        );
+        assert_eq!(
+            format_progress(250, "over", &[]),
+            "(progress :percent 100 :message \"over\")"
+        );
+    }
+
+    #[test]
+    fn test_format_snapshot() {
+        assert_eq!(
+            format_snapshot("started", &[]),
+            "(snapshot :event \"started\")"
+        );
+    }
+
+    #[test]
+    fn test_format_snapshot_fields_reparse() {
+        use crate::{get_kw_str, parse_value};
+
+        let text = format_snapshot(
+            "step-done",
+            &[("step", "3"), ("goal", "build \"app\""), ("status", "ok")],
+        );
+        assert_eq!(
+            text,
+            "(snapshot :event \"step-done\" :step \"3\" :goal \"build \\\"app\\\"\" :status \"ok\")"
+        );
+
+        let parsed = parse_value(&text).unwrap();
+        assert_eq!(
+            parsed.as_cons().unwrap().car().as_symbol(),
+            Some("snapshot")
+        );
+        assert_eq!(
+            get_kw_str(&parsed, "event").unwrap().as_deref(),
+            Some("step-done")
+        );
+        assert_eq!(get_kw_str(&parsed, "step").unwrap().as_deref(), Some("3"));
+        assert_eq!(
+            get_kw_str(&parsed, "goal").unwrap().as_deref(),
+            Some("build \"app\"")
+        );
+        assert_eq!(
+            get_kw_str(&parsed, "status").unwrap().as_deref(),
+            Some("ok")
+        );
+    }
+
     #[test]
     fn test_serialize_string_list() {
         let items = vec!["a".to_string(), "b".to_string()];
@@ -1,7 +1,9 @@
+use super::prompt::PromptSpec;
 use anyhow::{Context, Result};
 use rustyline::error::ReadlineError;
-use rustyline::DefaultEditor;
+use rustyline::{Config, DefaultEditor};
 use std::future::Future;
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 
 /// Control flow for the line loop.
@@ -33,6 +35,12 @@ pub fn default_history_path(kind: HistoryKind) -> PathBuf {
     }
 }
 
+/// Handler for readline errors other than Ctrl-C and EOF.
+pub type ReadlineErrorHandler<'a> = Box<dyn FnMut(&ReadlineError) -> LoopControl + 'a>;
+
+/// Predicate telling the line loop that the accumulated input is incomplete.
+pub type ContinuationFn<'a> = Box<dyn Fn(&str) -> bool + 'a>;
+
 /// Configuration for the interactive line loop.
 pub struct LineLoopConfig<'a> {
     /// Function to generate the prompt string
@@ -41,10 +49,20 @@ pub struct LineLoopConfig<'a> {
     pub add_history: bool,
     /// Optional history file path
     pub history_file: Option<PathBuf>,
+    /// Optional maximum number of history entries kept (and saved)
+    pub max_history: Option<usize>,
+    /// Whether to skip a history entry identical to the previous one (default `true`)
+    pub ignore_dups: bool,
+    /// Whether to trim surrounding whitespace from each line (default `true`)
+    pub trim: bool,
     /// Handler for Ctrl-C interrupt
     pub on_interrupt: Box<dyn FnMut() -> LoopControl + 'a>,
     /// Handler for EOF
     pub on_eof: Box<dyn FnMut() -> LoopControl + 'a>,
+    /// Optional handler for other readline errors; without one they end the loop with an error
+    pub on_error: Option<ReadlineErrorHandler<'a>>,
+    /// Optional predicate that keeps reading while the accumulated input is incomplete
+    pub continuation: Option<ContinuationFn<'a>>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -74,8 +92,13 @@ impl<'a> LineLoopConfig<'a> {
             prompt: Box::new(prompt),
             add_history,
             history_file: None,
+            max_history: None,
+            ignore_dups: true,
+            trim: true,
             on_interrupt: Box::new(on_interrupt),
             on_eof: Box::new(on_eof),
+            on_error: None,
+            continuation: None,
         }
     }
 
@@ -84,6 +107,74 @@ impl<'a> LineLoopConfig<'a> {
         self.history_file = Some(path.into());
         self
     }
+
+    /// Generate the prompt from a [`PromptSpec`].
+    ///
+    /// Segment colors are used only when stdout is a terminal.
+    pub fn with_prompt_spec(self, spec: impl FnMut() -> PromptSpec + 'a) -> Self {
+        let use_color = std::io::stdout().is_terminal();
+        self.with_prompt_spec_color(spec, use_color)
+    }
+
+    fn with_prompt_spec_color(
+        mut self,
+        mut spec: impl FnMut() -> PromptSpec + 'a,
+        use_color: bool,
+    ) -> Self {
+        self.prompt = Box::new(move || spec().render(use_color));
+        self
+    }
+
+    /// Set the maximum number of history entries to keep.
+    ///
+    /// Older entries are dropped, so the saved history file stays within the limit.
+    pub fn with_max_history(mut self, max: usize) -> Self {
+        self.max_history = Some(max);
+        self
+    }
+
+    /// Set whether consecutive identical lines are stored in history only once.
+    pub fn with_ignore_dups(mut self, ignore_dups: bool) -> Self {
+        self.ignore_dups = ignore_dups;
+        self
+    }
+
+    /// Set whether lines are trimmed before being passed on.
+    ///
+    /// Lines that are blank after trimming are skipped either way.
+    pub fn with_trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Set a handler for readline errors other than Ctrl-C and EOF.
+    ///
+    /// Returning [`LoopControl::Continue`] skips past the error and prompts
+    /// again; [`LoopControl::Break`] ends the loop normally.
+    pub fn with_on_error(
+        mut self,
+        on_error: impl FnMut(&ReadlineError) -> LoopControl + 'a,
+    ) -> Self {
+        self.on_error = Some(Box::new(on_error));
+        self
+    }
+
+    /// Set a predicate that asks for more input while it returns `true`.
+    ///
+    /// Lines are joined with `\n` before being passed on, so a form with
+    /// unbalanced parens can be continued on the next line.
+    pub fn with_continuation(mut self, continuation: impl Fn(&str) -> bool + 'a) -> Self {
+        self.continuation = Some(Box::new(continuation));
+        self
+    }
+}
+
+fn create_editor(cfg: &LineLoopConfig<'_>) -> Result<DefaultEditor> {
+    let mut builder = Config::builder().history_ignore_dups(cfg.ignore_dups)?;
+    if let Some(max) = cfg.max_history {
+        builder = builder.max_history_size(max)?;
+    }
+    DefaultEditor::with_config(builder.build()).context("Failed to initialize line editor")
 }
 
 fn try_load_history(editor: &mut DefaultEditor, path: &Path) {
@@ -94,71 +185,155 @@ fn try_save_history(editor: &mut DefaultEditor, path: &Path) {
     let _ = editor.save_history(path);
 }
 
-fn read_next_nonempty_line<'a>(
-    editor: &mut DefaultEditor,
-    cfg: &mut LineLoopConfig<'a>,
-) -> Result<Option<String>> {
+/// Create the editor and load the history file, if history is enabled.
+fn open_editor(cfg: &LineLoopConfig<'_>) -> Result<DefaultEditor> {
+    let mut editor = create_editor(cfg)?;
+    if cfg.add_history {
+        if let Some(path) = cfg.history_file.as_deref() {
+            try_load_history(&mut editor, path);
+        }
+    }
+    Ok(editor)
+}
+
+/// Write the history file now, if history is enabled and a file is set.
+fn save_history_now(editor: &mut DefaultEditor, cfg: &LineLoopConfig<'_>) {
+    if cfg.add_history {
+        if let Some(path) = cfg.history_file.as_deref() {
+            try_save_history(editor, path);
+        }
+    }
+}
+
+fn handle_readline_error(cfg: &mut LineLoopConfig<'_>, e: ReadlineError) -> Result<LoopControl> {
+    match classify_readline_error(&e) {
+        ReadlineErrorKind::Interrupted => Ok((cfg.on_interrupt)()),
+        ReadlineErrorKind::Eof => Ok((cfg.on_eof)()),
+        ReadlineErrorKind::Other => match cfg.on_error.as_mut() {
+            Some(on_error) => Ok(on_error(&e)),
+            None => Err(e).context("Readline error"),
+        },
+    }
+}
+
+fn read_next_input<'a, R>(cfg: &mut LineLoopConfig<'a>, mut readline: R) -> Result<Option<String>>
+where
+    R: FnMut(&str) -> std::result::Result<String, ReadlineError>,
+{
+    let mut pending: Option<String> = None;
     loop {
         let prompt = (cfg.prompt)();
-        let line = match editor.readline(&prompt) {
+        let line = match readline(&prompt) {
             Ok(l) => l,
-            Err(e) => match classify_readline_error(&e) {
-                ReadlineErrorKind::Interrupted => {
-                    if matches!((cfg.on_interrupt)(), LoopControl::Break) {
-                        return Ok(None);
-                    }
-                    continue;
-                }
-                ReadlineErrorKind::Eof => {
-                    if matches!((cfg.on_eof)(), LoopControl::Break) {
-                        return Ok(None);
-                    }
-                    continue;
+            Err(e) => {
+                // An interrupt or EOF abandons any partially entered input
+                pending = None;
+                if matches!(handle_readline_error(cfg, e)?, LoopControl::Break) {
+                    return Ok(None);
                 }
-                ReadlineErrorKind::Other => return Err(e).context("Readline error"),
-            },
+                continue;
+            }
         };
 
-        let line = line.trim().to_string();
-        if line.is_empty() {
-            continue;
-        }
-
-        if cfg.add_history {
-            let _ = editor.add_history_entry(&line);
-            if let Some(path) = cfg.history_file.as_deref() {
-                try_save_history(editor, path);
+        let line = if cfg.trim { line.trim() } else { line.as_str() };
+        let input = match pending.take() {
+            Some(mut input) => {
+                input.push('\n');
+                input.push_str(line);
+                input
             }
+            None if line.trim().is_empty() => continue,
+            None => line.to_string(),
+        };
+
+        if cfg.continuation.as_ref().is_some_and(|more| more(&input)) {
+            pending = Some(input);
+            continue;
         }
 
-        return Ok(Some(line));
+        return Ok(Some(input));
     }
 }
 
-/// Run a synchronous interactive line loop.
-pub fn run_line_loop<'a, F>(mut cfg: LineLoopConfig<'a>, mut on_line: F) -> Result<()>
+fn editor_readline(
+    editor: &mut DefaultEditor,
+    prompt: &str,
+) -> std::result::Result<String, ReadlineError> {
+    editor.readline(prompt)
+}
+
+fn read_next_nonempty_line<'a, R>(
+    editor: &mut DefaultEditor,
+    cfg: &mut LineLoopConfig<'a>,
+    readline: &mut R,
+) -> Result<Option<String>>
 where
-    F: FnMut(&str) -> Result<LoopControl> + 'a,
+    R: FnMut(&mut DefaultEditor, &str) -> std::result::Result<String, ReadlineError>,
 {
-    let mut editor = DefaultEditor::new().context("Failed to initialize line editor")?;
+    let Some(line) = read_next_input(cfg, |prompt| readline(editor, prompt))? else {
+        return Ok(None);
+    };
 
     if cfg.add_history {
-        if let Some(path) = cfg.history_file.as_deref() {
-            try_load_history(&mut editor, path);
-        }
+        let _ = editor.add_history_entry(&line);
+        save_history_now(editor, cfg);
     }
 
-    while let Some(line) = read_next_nonempty_line(&mut editor, &mut cfg)? {
+    Ok(Some(line))
+}
+
+fn handle_lines<R, F>(
+    editor: &mut DefaultEditor,
+    cfg: &mut LineLoopConfig<'_>,
+    mut readline: R,
+    mut on_line: F,
+) -> Result<()>
+where
+    R: FnMut(&mut DefaultEditor, &str) -> std::result::Result<String, ReadlineError>,
+    F: FnMut(&str) -> Result<LoopControl>,
+{
+    while let Some(line) = read_next_nonempty_line(editor, cfg, &mut readline)? {
         match on_line(&line)? {
             LoopControl::Continue => {}
             LoopControl::Break => break,
         }
     }
-
     Ok(())
 }
 
+/// Run the sync loop body, then save history however the loop ended.
+fn drive_line_loop<R, F>(
+    editor: &mut DefaultEditor,
+    cfg: &mut LineLoopConfig<'_>,
+    readline: R,
+    on_line: F,
+) -> Result<()>
+where
+    R: FnMut(&mut DefaultEditor, &str) -> std::result::Result<String, ReadlineError>,
+    F: FnMut(&str) -> Result<LoopControl>,
+{
+    let result = handle_lines(editor, cfg, readline, on_line);
+    save_history_now(editor, cfg);
+    result
+}
+
+/// Run a synchronous interactive line loop.
+///
+/// With history enabled, each line is saved as it is entered and the history
+/// file is written once more when the loop ends, whether by
+/// [`LoopControl::Break`], EOF, or an error.
+pub fn run_line_loop<'a, F>(mut cfg: LineLoopConfig<'a>, on_line: F) -> Result<()>
+where
+    F: FnMut(&str) -> Result<LoopControl> + 'a,
+{
+    let mut editor = open_editor(&cfg)?;
+    drive_line_loop(&mut editor, &mut cfg, editor_readline, on_line)
+}
+
 /// Run an asynchronous interactive line loop.
+///
+/// History is saved the same way as in [`run_line_loop`], including a final
+/// save when the loop ends.
 pub async fn run_line_loop_async<'a, F, Fut>(
     mut cfg: LineLoopConfig<'a>,
     mut on_line: F,
@@ -167,22 +342,22 @@ where
     F: FnMut(String) -> Fut + 'a,
     Fut: Future<Output = Result<LoopControl>> + 'a,
 {
-    let mut editor = DefaultEditor::new().context("Failed to initialize line editor")?;
-
-    if cfg.add_history {
-        if let Some(path) = cfg.history_file.as_deref() {
-            try_load_history(&mut editor, path);
-        }
-    }
+    let mut editor = open_editor(&cfg)?;
+    let mut readline = editor_readline;
 
-    while let Some(line) = read_next_nonempty_line(&mut editor, &mut cfg)? {
-        match on_line(line).await? {
-            LoopControl::Continue => {}
-            LoopControl::Break => break,
+    let result = async {
+        while let Some(line) = read_next_nonempty_line(&mut editor, &mut cfg, &mut readline)? {
+            match on_line(line).await? {
+                LoopControl::Continue => {}
+                LoopControl::Break => break,
+            }
         }
+        Ok::<(), anyhow::Error>(())
     }
+    .await;
 
-    Ok(())
+    save_history_now(&mut editor, &cfg);
+    result
 }
 
 #[cfg(test)]
@@ -209,10 +384,158 @@ mod tests {
 
     #[test]
     fn test_classify_readline_error_other() {
-        let e = ReadlineError::Io(std::io::Error::new(std::io::ErrorKind::Other, "x"));
+        let e = ReadlineError::Io(std::io::Error::other("x"));
         assert_eq!(classify_readline_error(&e), ReadlineErrorKind::Other);
     }
 
+    fn test_config<'a>() -> LineLoopConfig<'a> {
+        LineLoopConfig::new(
+            || "> ".to_string(),
+            false,
+            || LoopControl::Continue,
+            || LoopControl::Break,
+        )
+    }
+
+    fn other_error() -> ReadlineError {
+        ReadlineError::Io(std::io::Error::other("glitch"))
+    }
+
+    #[test]
+    fn test_handle_readline_error_other_without_handler() {
+        let mut cfg = test_config();
+        assert!(handle_readline_error(&mut cfg, other_error()).is_err());
+    }
+
+    #[test]
+    fn test_handle_readline_error_other_with_handler() {
+        let mut seen = 0;
+        {
+            let mut cfg = test_config().with_on_error(|_| {
+                seen += 1;
+                LoopControl::Continue
+            });
+            let control = handle_readline_error(&mut cfg, other_error()).unwrap();
+            assert_eq!(control, LoopControl::Continue);
+        }
+        assert_eq!(seen, 1);
+
+        let mut cfg = test_config().with_on_error(|_| LoopControl::Break);
+        let control = handle_readline_error(&mut cfg, other_error()).unwrap();
+        assert_eq!(control, LoopControl::Break);
+    }
+
+    #[test]
+    fn test_continuation_joins_lines() {
+        let mut lines = vec!["(tool :a 1", ":b 2)", "(next)"].into_iter();
+        let mut cfg = test_config()
+            .with_continuation(|input| input.matches('(').count() > input.matches(')').count());
+        let mut readline = |_: &str| lines.next().map(String::from).ok_or(ReadlineError::Eof);
+
+        let first = read_next_input(&mut cfg, &mut readline).unwrap();
+        assert_eq!(first.as_deref(), Some("(tool :a 1\n:b 2)"));
+
+        let second = read_next_input(&mut cfg, &mut readline).unwrap();
+        assert_eq!(second.as_deref(), Some("(next)"));
+
+        assert_eq!(read_next_input(&mut cfg, &mut readline).unwrap(), None);
+    }
+
+    #[test]
+    fn test_trim_option() {
+        let inputs = ["  (tool :a 1)  ", "   ", "\tnext"];
+
+        let mut lines = inputs.into_iter();
+        let mut readline = |_: &str| lines.next().map(String::from).ok_or(ReadlineError::Eof);
+        let mut cfg = test_config();
+        let first = read_next_input(&mut cfg, &mut readline).unwrap();
+        assert_eq!(first.as_deref(), Some("(tool :a 1)"));
+        let second = read_next_input(&mut cfg, &mut readline).unwrap();
+        assert_eq!(second.as_deref(), Some("next"));
+
+        let mut lines = inputs.into_iter();
+        let mut readline = |_: &str| lines.next().map(String::from).ok_or(ReadlineError::Eof);
+        let mut cfg = test_config().with_trim(false);
+        let first = read_next_input(&mut cfg, &mut readline).unwrap();
+        assert_eq!(first.as_deref(), Some("  (tool :a 1)  "));
+        let second = read_next_input(&mut cfg, &mut readline).unwrap();
+        assert_eq!(second.as_deref(), Some("\tnext"));
+    }
+
+    #[test]
+    fn test_history_ignore_dups_and_max() {
+        let mut editor = create_editor(&test_config()).unwrap();
+        for line in ["a", "a", "b", "b", "a"] {
+            let _ = editor.add_history_entry(line);
+        }
+        // Only consecutive duplicates are collapsed
+        assert_eq!(editor.history().len(), 3);
+
+        let mut editor = create_editor(&test_config().with_ignore_dups(false)).unwrap();
+        for line in ["a", "a", "b"] {
+            let _ = editor.add_history_entry(line);
+        }
+        assert_eq!(editor.history().len(), 3);
+
+        let mut editor = create_editor(&test_config().with_max_history(2)).unwrap();
+        for line in ["a", "b", "c", "d"] {
+            let _ = editor.add_history_entry(line);
+        }
+        assert_eq!(editor.history().len(), 2);
+    }
+
+    #[test]
+    fn test_prompt_spec_terminal_detection() {
+        use crate::interactive::PromptColor;
+
+        let spec = || {
+            PromptSpec::new()
+                .colored("mcp", PromptColor::Green)
+                .plain("> ")
+        };
+
+        let mut cfg = test_config().with_prompt_spec_color(spec, true);
+        assert_eq!((cfg.prompt)(), "\x1b[32mmcp\x1b[0m> ");
+
+        let mut cfg = test_config().with_prompt_spec_color(spec, false);
+        assert_eq!((cfg.prompt)(), "mcp> ");
+    }
+
+    #[test]
+    fn test_history_saved_when_loop_breaks() {
+        let dir = tempdir().unwrap();
+        let history_file = dir.path().join("history.txt");
+        let mut cfg = LineLoopConfig::new(
+            || "> ".to_string(),
+            true,
+            || LoopControl::Continue,
+            || LoopControl::Break,
+        )
+        .with_history_file(&history_file);
+        let mut editor = open_editor(&cfg).unwrap();
+
+        let mut lines = vec!["first", "quit", "never read"].into_iter();
+        let mut seen = Vec::new();
+        drive_line_loop(
+            &mut editor,
+            &mut cfg,
+            |_, _| lines.next().map(String::from).ok_or(ReadlineError::Eof),
+            |line| {
+                seen.push(line.to_string());
+                Ok(if line == "quit" {
+                    LoopControl::Break
+                } else {
+                    LoopControl::Continue
+                })
+            },
+        )
+        .unwrap();
+        assert_eq!(seen, vec!["first", "quit"]);
+
+        let saved = std::fs::read_to_string(&history_file).unwrap();
+        assert_eq!(saved.lines().last(), Some("quit"));
+    }
+
     #[test]
     fn test_history_persistence() {
         let dir = tempdir().unwrap();
@@ -24,5 +24,8 @@
 
 /// Line loop implementation with rustyline.
 pub mod line_loop;
+/// Structured prompts with optional color.
+pub mod prompt;
 
-pub use line_loop::{default_history_path, run_line_loop, run_line_loop_async, HistoryKind, LineLoopConfig, LoopControl};
+pub use line_loop::{default_history_path, run_line_loop, run_line_loop_async, ContinuationFn, HistoryKind, LineLoopConfig, LoopControl, ReadlineErrorHandler};
+pub use prompt::{PromptColor, PromptSegment, PromptSpec};
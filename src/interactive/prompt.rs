@@ -0,0 +1,127 @@
+/// ANSI color for a prompt segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptColor {
+    /// Red
+    Red,
+    /// Green
+    Green,
+    /// Yellow
+    Yellow,
+    /// Blue
+    Blue,
+    /// Magenta
+    Magenta,
+    /// Cyan
+    Cyan,
+    /// Bold, without changing the color
+    Bold,
+}
+
+impl PromptColor {
+    fn ansi_code(self) -> &'static str {
+        match self {
+            PromptColor::Red => "31",
+            PromptColor::Green => "32",
+            PromptColor::Yellow => "33",
+            PromptColor::Blue => "34",
+            PromptColor::Magenta => "35",
+            PromptColor::Cyan => "36",
+            PromptColor::Bold => "1",
+        }
+    }
+}
+
+/// A piece of prompt text with an optional color.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptSegment {
+    /// Text of the segment
+    pub text: String,
+    /// Color applied when rendering for a terminal
+    pub color: Option<PromptColor>,
+}
+
+/// A prompt made of segments, rendered with ANSI colors only for terminals.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::interactive::{PromptColor, PromptSpec};
+///
+/// let spec = PromptSpec::new()
+///     .colored("mcp", PromptColor::Green)
+///     .plain(":3")
+///     .plain("> ");
+///
+/// assert_eq!(spec.render(false), "mcp:3> ");
+/// assert_eq!(spec.render(true), "\x1b[32mmcp\x1b[0m:3> ");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PromptSpec {
+    /// Segments in display order
+    pub segments: Vec<PromptSegment>,
+}
+
+impl PromptSpec {
+    /// Create an empty prompt.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an uncolored segment.
+    pub fn plain(mut self, text: impl Into<String>) -> Self {
+        self.segments.push(PromptSegment {
+            text: text.into(),
+            color: None,
+        });
+        self
+    }
+
+    /// Append a colored segment.
+    pub fn colored(mut self, text: impl Into<String>, color: PromptColor) -> Self {
+        self.segments.push(PromptSegment {
+            text: text.into(),
+            color: Some(color),
+        });
+        self
+    }
+
+    /// Render the prompt, with ANSI escapes if `use_color` is true and as plain text otherwise.
+    pub fn render(&self, use_color: bool) -> String {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment.color {
+                Some(color) if use_color => {
+                    out.push_str(&format!(
+                        "\x1b[{}m{}\x1b[0m",
+                        color.ansi_code(),
+                        segment.text
+                    ));
+                }
+                _ => out.push_str(&segment.text),
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_plain_without_terminal() {
+        let spec = PromptSpec::new()
+            .colored("repl", PromptColor::Cyan)
+            .plain(" ")
+            .colored("!", PromptColor::Red);
+        assert_eq!(spec.render(false), "repl !");
+    }
+
+    #[test]
+    fn test_render_colored_for_terminal() {
+        let spec = PromptSpec::new()
+            .colored("repl", PromptColor::Cyan)
+            .plain("> ");
+        assert_eq!(spec.render(true), "\x1b[36mrepl\x1b[0m> ");
+    }
+}
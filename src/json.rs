@@ -0,0 +1,271 @@
+//! Conversion between S-expressions and JSON values.
+//!
+//! Useful for bridging S-expression tool calls into JSON-based logging or
+//! telemetry, and for accepting JSON input that the rest of the stack should
+//! see as a canonical S-expression.
+//!
+//! # Mapping
+//!
+//! | S-expression            | JSON                          |
+//! |-------------------------|-------------------------------|
+//! | `"text"`                | `"text"`                      |
+//! | `42`, `1.5`             | number                        |
+//! | `#t` / `#f`             | `true` / `false`              |
+//! | `sym`, `:kw`            | `"sym"`, `"kw"`               |
+//! | `()`                    | `[]`                          |
+//! | `(1 2 3)`               | `[1, 2, 3]`                   |
+//! | `(:a 1 :b 2)`           | `{"a": 1, "b": 2}`            |
+//! | `(tool 1 :a 2)`         | `["tool", 1, {"a": 2}]`       |
+//!
+//! A list that starts with a keyword becomes an object. Otherwise the items
+//! before the first keyword become array elements, and any trailing
+//! keyword arguments are collected into one object at the end of the array.
+//!
+//! Improper lists and dotted pairs such as `(a . b)` have no JSON
+//! equivalent and are rejected, as are characters, byte vectors,
+//! non-finite floats, duplicate keywords, a keyword without a value, and a
+//! positional value after the keyword arguments.
+//!
+//! [`json_to_sexpr`] goes the other way: objects become keyword-argument
+//! lists, arrays become proper lists, and `null` becomes `()`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use mcp_tools::parse_value;
+//! use mcp_tools::json::to_json;
+//!
+//! let value = parse_value("(tool :name \"x\" :items (1 2 3))").unwrap();
+//! let json = to_json(&value).unwrap();
+//! assert_eq!(json[0], "tool");
+//! assert_eq!(json[1]["name"], "x");
+//! assert_eq!(json[1]["items"][2], 3);
+//! ```
+
+use crate::{is_explicit_kw, is_plain_symbol, normalize_kw, quote_str, render_list};
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Number, Value};
+
+/// Convert a parsed S-expression into a `serde_json::Value`.
+///
+/// See the [module documentation](self) for the mapping and the values that
+/// are rejected.
+pub fn to_json(value: &lexpr::Value) -> Result<Value> {
+    match value {
+        lexpr::Value::String(s) => Ok(Value::String(s.to_string())),
+        lexpr::Value::Symbol(s) => Ok(Value::String(s.to_string())),
+        lexpr::Value::Keyword(_) => Ok(Value::String(keyword_name(value)?.to_string())),
+        lexpr::Value::Bool(b) => Ok(Value::Bool(*b)),
+        lexpr::Value::Number(n) => number_to_json(n),
+        lexpr::Value::Null => Ok(Value::Array(Vec::new())),
+        lexpr::Value::Cons(_) => list_to_json(value),
+        lexpr::Value::Vector(items) => items
+            .iter()
+            .map(to_json)
+            .collect::<Result<_>>()
+            .map(Value::Array),
+        other => Err(anyhow!("cannot convert {} to JSON", other)),
+    }
+}
+
+fn keyword_name(value: &lexpr::Value) -> Result<&str> {
+    normalize_kw(value).ok_or_else(|| anyhow!("expected keyword, got {}", value))
+}
+
+fn number_to_json(n: &lexpr::Number) -> Result<Value> {
+    if let Some(i) = n.as_i64() {
+        return Ok(Value::from(i));
+    }
+    if let Some(u) = n.as_u64() {
+        return Ok(Value::from(u));
+    }
+    n.as_f64()
+        .and_then(Number::from_f64)
+        .map(Value::Number)
+        .ok_or_else(|| anyhow!("cannot convert number {} to JSON", n))
+}
+
+fn list_to_json(value: &lexpr::Value) -> Result<Value> {
+    let mut positional = Vec::new();
+    let mut cur = value;
+    while let Some(cons) = cur.as_cons() {
+        if is_explicit_kw(cons.car()) {
+            break;
+        }
+        positional.push(to_json(cons.car())?);
+        cur = cons.cdr();
+    }
+
+    let mut fields = Map::new();
+    while let Some(cons) = cur.as_cons() {
+        if !is_explicit_kw(cons.car()) {
+            return Err(anyhow!(
+                "positional value {} after keyword arguments",
+                cons.car()
+            ));
+        }
+        let key = keyword_name(cons.car())?;
+        let val_cons = cons
+            .cdr()
+            .as_cons()
+            .ok_or_else(|| anyhow!("expected value after keyword :{}", key))?;
+        if fields
+            .insert(key.to_string(), to_json(val_cons.car())?)
+            .is_some()
+        {
+            return Err(anyhow!("duplicate keyword :{}", key));
+        }
+        cur = val_cons.cdr();
+    }
+
+    if !cur.is_null() {
+        return Err(anyhow!(
+            "cannot convert improper list to JSON (dotted tail {})",
+            cur
+        ));
+    }
+
+    if positional.is_empty() {
+        return Ok(Value::Object(fields));
+    }
+    if !fields.is_empty() {
+        positional.push(Value::Object(fields));
+    }
+    Ok(Value::Array(positional))
+}
+
+/// Render a `serde_json::Value` as S-expression text.
+///
+/// Objects become keyword-argument lists (`{"a": 1}` → `(:a 1)`), arrays
+/// become proper lists, strings are quoted with [`quote_str`], and numbers
+/// and booleans (`#t`/`#f`) are written as bare atoms. `null` renders as
+/// `()`. Object keys are written after a `:` and must read back as that
+/// keyword, so a key containing whitespace, parentheses, `;` or the like is
+/// an error.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::json::json_to_sexpr;
+/// use serde_json::json;
+///
+/// let value = json!({"name": "x", "items": [1, 2], "dry-run": true});
+/// assert_eq!(json_to_sexpr(&value)?, "(:dry-run #t :items (1 2) :name \"x\")");
+/// assert!(json_to_sexpr(&json!({"a) (b": 1})).is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn json_to_sexpr(value: &Value) -> Result<String> {
+    Ok(match value {
+        Value::Null => "()".to_string(),
+        Value::Bool(true) => "#t".to_string(),
+        Value::Bool(false) => "#f".to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => quote_str(s),
+        Value::Array(items) => {
+            let items = items
+                .iter()
+                .map(json_to_sexpr)
+                .collect::<Result<Vec<_>>>()?;
+            format!("({})", render_list(items))
+        }
+        Value::Object(fields) => {
+            let fields = fields
+                .iter()
+                .map(|(key, v)| {
+                    let keyword = format!(":{}", key);
+                    if !is_plain_symbol(&keyword) {
+                        return Err(anyhow!("object key {:?} is not a valid keyword name", key));
+                    }
+                    Ok(format!("{} {}", keyword, json_to_sexpr(v)?))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            format!("({})", render_list(fields))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_value;
+    use serde_json::json;
+
+    fn convert(input: &str) -> Result<Value> {
+        to_json(&parse_value(input).unwrap())
+    }
+
+    #[test]
+    fn test_nested_tool_call() {
+        let json = convert("(tool :name \"x\" :items (1 2 3))").unwrap();
+        assert_eq!(json, json!(["tool", {"name": "x", "items": [1, 2, 3]}]));
+    }
+
+    #[test]
+    fn test_scalars() {
+        assert_eq!(convert("\"hi\"").unwrap(), json!("hi"));
+        assert_eq!(convert("-7").unwrap(), json!(-7));
+        assert_eq!(convert("1.5").unwrap(), json!(1.5));
+        assert_eq!(convert("#t").unwrap(), json!(true));
+        assert_eq!(convert("#f").unwrap(), json!(false));
+        assert_eq!(convert("fast").unwrap(), json!("fast"));
+        assert_eq!(convert("()").unwrap(), json!([]));
+    }
+
+    #[test]
+    fn test_keyword_lists() {
+        assert_eq!(
+            convert("(:retries 3 :mode fast)").unwrap(),
+            json!({"retries": 3, "mode": "fast"})
+        );
+        assert_eq!(
+            convert("(resize 800 600 :mode fit)").unwrap(),
+            json!(["resize", 800, 600, {"mode": "fit"}])
+        );
+    }
+
+    #[test]
+    fn test_rejected_forms() {
+        assert!(convert("(a . b)").is_err());
+        assert!(convert("(1 2 . 3)").is_err());
+        assert!(convert("(tool :name)").is_err());
+        assert!(convert("(tool :a 1 :a 2)").is_err());
+        assert!(convert("(tool :a 1 extra)").is_err());
+    }
+
+    #[test]
+    fn test_json_to_sexpr_mixed_object() {
+        let value = json!({
+            "name": "say \"hi\"",
+            "count": 3,
+            "ratio": 0.5,
+            "enabled": false,
+            "tags": ["a", "b"],
+            "nested": {"matrix": [[1, 2], [3, 4]]}
+        });
+        let rendered = json_to_sexpr(&value).unwrap();
+        let parsed = parse_value(&rendered).unwrap();
+        assert_eq!(to_json(&parsed).unwrap(), value);
+    }
+
+    #[test]
+    fn test_json_to_sexpr_scalars() {
+        assert_eq!(json_to_sexpr(&json!(null)).unwrap(), "()");
+        assert_eq!(json_to_sexpr(&json!(true)).unwrap(), "#t");
+        assert_eq!(json_to_sexpr(&json!(-2)).unwrap(), "-2");
+        assert_eq!(json_to_sexpr(&json!("a\nb")).unwrap(), "\"a\\nb\"");
+        assert_eq!(json_to_sexpr(&json!([1, [2, 3]])).unwrap(), "(1 (2 3))");
+    }
+
+    #[test]
+    fn test_json_to_sexpr_rejects_bad_keys() {
+        for key in ["a) (evil", "two words", "a;b"] {
+            let err = json_to_sexpr(&json!({ key: 1 })).unwrap_err();
+            assert!(err.to_string().contains("not a valid keyword"), "{:?}", key);
+        }
+        assert!(json_to_sexpr(&json!([{"ok": {"bad key": 1}}])).is_err());
+
+        let value = json!({"café": 1, "a#b": 2});
+        let parsed = parse_value(&json_to_sexpr(&value).unwrap()).unwrap();
+        assert_eq!(to_json(&parsed).unwrap(), value);
+    }
+}
@@ -20,7 +20,10 @@
 //! - **`format`**: S-expression response formatting utilities
 //! - **`extract`**: Type-safe argument extraction with type conversion
 //! - **`persistence`**: SQLite-based tool call logging and observability
+//! - **`persistence-async`**: Tokio wrapper for persistence that runs queries off the async runtime
 //! - **`log-viewer`**: Interactive CLI for querying tool call logs
+//! - **`json`**: Convert between S-expressions and `serde_json` values
+//! - **`base64`**: Inline binary data in [`TextRef`] as `(data "base64...")`
 //! - **`router`**: MCP server routing patterns with handler registration
 //! - **`errors`**: Typed error patterns and examples using thiserror
 //!
@@ -66,6 +69,9 @@ pub mod persistence;
 #[cfg(feature = "log-viewer")]
 pub mod log_viewer;
 
+#[cfg(feature = "json")]
+pub mod json;
+
 #[cfg(feature = "router")]
 pub mod router;
 
@@ -73,6 +79,7 @@ pub mod router;
 pub mod errors;
 
 use anyhow::{anyhow, Context, Result};
+use std::path::{Component, Path, PathBuf};
 
 /// Parse a full S-expression string into a `lexpr::Value`.
 ///
@@ -88,7 +95,84 @@ pub fn parse_value(input: &str) -> Result<lexpr::Value> {
     lexpr::from_str(input).context("failed to parse s-expression")
 }
 
-fn normalize_kw(key: &lexpr::Value) -> Option<&str> {
+/// Parse a sequence of whitespace-separated top-level S-expressions.
+///
+/// Returns an empty `Vec` for empty or whitespace-only input. On a syntax
+/// error the message includes the byte offset where parsing failed.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::parse_values;
+///
+/// let values = parse_values("(a :x 1)\n(b :y 2)").unwrap();
+/// assert_eq!(values.len(), 2);
+/// assert!(parse_values("   ").unwrap().is_empty());
+/// ```
+pub fn parse_values(input: &str) -> Result<Vec<lexpr::Value>> {
+    let mut out = Vec::new();
+    for_each_value(input, |v| {
+        out.push(v);
+        Ok(())
+    })?;
+    Ok(out)
+}
+
+/// Parse concatenated top-level S-expressions one at a time, passing each to `f`.
+///
+/// Unlike [`parse_values`], no values are retained, so this suits large
+/// inputs such as concatenated logs. Stops at the first error from `f`
+/// and returns it unchanged; syntax errors are reported with their byte
+/// offset as in [`parse_values`], after `f` has seen every earlier form.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::for_each_value;
+///
+/// let mut count = 0;
+/// for_each_value("(a) (b) (c)", |_| {
+///     count += 1;
+///     Ok(())
+/// })?;
+/// assert_eq!(count, 3);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn for_each_value(input: &str, mut f: impl FnMut(lexpr::Value) -> Result<()>) -> Result<()> {
+    let mut parser = lexpr::Parser::from_str(input);
+
+    loop {
+        match parser.next_value() {
+            Ok(Some(v)) => f(v)?,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                return Err(match e.location() {
+                    Some(loc) => {
+                        let offset = byte_offset(input, loc.line(), loc.column());
+                        anyhow!(
+                            "failed to parse s-expression at byte offset {}: {}",
+                            offset,
+                            e
+                        )
+                    }
+                    None => anyhow!("failed to parse s-expression: {}", e),
+                });
+            }
+        }
+    }
+}
+
+/// Convert a 1-based line and 0-based column into a byte offset within `input`.
+fn byte_offset(input: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = input
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    (line_start + column).min(input.len())
+}
+
+pub(crate) fn normalize_kw(key: &lexpr::Value) -> Option<&str> {
     if let Some(sym) = key.as_symbol() {
         Some(sym.strip_prefix(':').unwrap_or(sym))
     } else if let Some(kw) = key.as_keyword() {
@@ -100,7 +184,8 @@ fn normalize_kw(key: &lexpr::Value) -> Option<&str> {
 
 /// Extract the raw `lexpr::Value` for a keyword argument from a tool-call form.
 ///
-/// Returns `Ok(None)` when the keyword is not present.
+/// Positional arguments before the keywords, as in `(resize 800 600 :mode fit)`,
+/// are skipped. Returns `Ok(None)` when the keyword is not present.
 ///
 /// # Example
 ///
@@ -116,7 +201,43 @@ pub fn get_kw_value(root: &lexpr::Value, key: &str) -> Result<Option<lexpr::Valu
         .as_cons()
         .ok_or_else(|| anyhow!("expected list (tool call form)"))?;
 
-    let mut cur = list.cdr();
+    find_kw_value(list.cdr(), key)
+}
+
+/// Skip the positional arguments at `start`, returning the keyword section.
+///
+/// A leading run of non-symbol values, like the `800 600` in
+/// `(resize 800 600 :mode fit)`, is skipped up to the first explicit keyword.
+/// A symbol in that run may be a bare key, as in `(tool name "x" :mode fit)`,
+/// so then scanning starts at `start` unchanged.
+fn kw_section(start: &lexpr::Value) -> &lexpr::Value {
+    let mut cur = start;
+    while let Some(cons) = cur.as_cons() {
+        if is_explicit_kw(cons.car()) {
+            return cur;
+        }
+        if normalize_kw(cons.car()).is_some() {
+            return start;
+        }
+        cur = cons.cdr();
+    }
+    start
+}
+
+fn find_kw_value(start: &lexpr::Value, key: &str) -> Result<Option<lexpr::Value>> {
+    find_kw_value_by(start, |found| found == key)
+}
+
+/// Scan a keyword section and return the value of the first key that `matches`.
+///
+/// The whole section is always scanned, so a dangling keyword anywhere in it
+/// is an error even when the requested key appears earlier.
+fn find_kw_value_by(
+    start: &lexpr::Value,
+    matches: impl Fn(&str) -> bool,
+) -> Result<Option<lexpr::Value>> {
+    let mut result = None;
+    let mut cur = kw_section(start);
     while let Some(cons) = cur.as_cons() {
         let k = cons.car();
         let Some(found) = normalize_kw(k) else {
@@ -127,245 +248,1611 @@ pub fn get_kw_value(root: &lexpr::Value, key: &str) -> Result<Option<lexpr::Valu
         let val_cons = cur
             .as_cons()
             .ok_or_else(|| anyhow!("expected value after keyword :{}", found))?;
-        let v = val_cons.car();
 
-        if found == key {
-            return Ok(Some(v.clone()));
+        if result.is_none() && matches(found) {
+            result = Some(val_cons.car().clone());
         }
 
         cur = val_cons.cdr();
     }
 
-    Ok(None)
+    Ok(result)
 }
 
-/// Extract a keyword argument as a string.
+/// Extract the raw `lexpr::Value` for a keyword, erroring if it appears more than once.
 ///
-/// Returns `Ok(None)` when the keyword is not present.
+/// Unlike [`get_kw_value`], which returns the first match, this scans the
+/// whole keyword section so that `(tool :name "a" :name "b")` is rejected.
 ///
 /// # Example
 ///
 /// ```rust
-/// use mcp_tools::{parse_value, get_kw_str};
+/// use mcp_tools::{parse_value, get_kw_value_unique};
 ///
-/// let value = parse_value("(tool :name \"example\")").unwrap();
-/// assert_eq!(get_kw_str(&value, "name").unwrap(), Some("example".to_string()));
-/// assert_eq!(get_kw_str(&value, "missing").unwrap(), None);
+/// let value = parse_value("(tool :name \"a\" :name \"b\")").unwrap();
+/// assert!(get_kw_value_unique(&value, "name").is_err());
 /// ```
-pub fn get_kw_str(root: &lexpr::Value, key: &str) -> Result<Option<String>> {
-    match get_kw_value(root, key)? {
-        None => Ok(None),
-        Some(v) => v
-            .as_str()
-            .map(|s| Some(s.to_string()))
-            .ok_or_else(|| anyhow!(":{} must be a string", key)),
+pub fn get_kw_value_unique(root: &lexpr::Value, key: &str) -> Result<Option<lexpr::Value>> {
+    let list = root
+        .as_cons()
+        .ok_or_else(|| anyhow!("expected list (tool call form)"))?;
+
+    let mut result = None;
+    let mut cur = kw_section(list.cdr());
+    while let Some(cons) = cur.as_cons() {
+        let Some(found) = normalize_kw(cons.car()) else {
+            break;
+        };
+
+        let val_cons = cons
+            .cdr()
+            .as_cons()
+            .ok_or_else(|| anyhow!("expected value after keyword :{}", found))?;
+
+        if found == key {
+            if result.is_some() {
+                return Err(anyhow!("duplicate keyword :{}", key));
+            }
+            result = Some(val_cons.car().clone());
+        }
+
+        cur = val_cons.cdr();
     }
+
+    Ok(result)
 }
 
-/// Extract a required keyword argument as a string.
-///
-/// Errors when missing.
+/// Extract a required keyword argument as a string, erroring on duplicates.
 ///
 /// # Example
 ///
 /// ```rust
-/// use mcp_tools::{parse_value, require_kw_str};
+/// use mcp_tools::{parse_value, require_kw_str_unique};
 ///
 /// let value = parse_value("(tool :name \"example\")").unwrap();
-/// assert_eq!(require_kw_str(&value, "name").unwrap(), "example");
+/// assert_eq!(require_kw_str_unique(&value, "name").unwrap(), "example");
 /// ```
-pub fn require_kw_str(root: &lexpr::Value, key: &str) -> Result<String> {
-    get_kw_str(root, key)?.ok_or_else(|| anyhow!("missing required keyword :{}", key))
+pub fn require_kw_str_unique(root: &lexpr::Value, key: &str) -> Result<String> {
+    let v = get_kw_value_unique(root, key)?
+        .ok_or_else(|| anyhow!("missing required keyword :{}", key))?;
+    str_from_value(&v, key)
 }
 
-/// Iterate over a proper list.
+/// List the normalized names of all keyword arguments in a tool-call form.
 ///
-/// Returns an error if `value` is not a list.
+/// Keys are returned in order of appearance, including duplicates.
+/// Positional arguments before the keywords are skipped, and scanning stops
+/// at the first non-keyword item after them, matching [`get_kw_value`].
 ///
 /// # Example
 ///
 /// ```rust
-/// use mcp_tools::{parse_value, iter_list};
+/// use mcp_tools::{parse_value, list_kw_keys};
 ///
-/// let value = parse_value("(a b c)").unwrap();
-/// let items: Vec<_> = iter_list(&value).unwrap().collect();
-/// assert_eq!(items.len(), 3);
+/// let value = parse_value("(tool :name \"x\" :count 3)").unwrap();
+/// assert_eq!(list_kw_keys(&value).unwrap(), vec!["name", "count"]);
 /// ```
-pub fn iter_list(value: &lexpr::Value) -> Result<impl Iterator<Item = lexpr::Value>> {
-    let mut out: Vec<lexpr::Value> = Vec::new();
-    let mut cur = value;
+pub fn list_kw_keys(root: &lexpr::Value) -> Result<Vec<String>> {
+    Ok(iter_kw_pairs(root)?.map(|(key, _)| key).collect())
+}
 
+/// Iterate over the keyword/value pairs of a tool-call form.
+///
+/// Keys are normalized (leading `:` stripped) and yielded in order of
+/// appearance, including duplicates. Positional arguments before the
+/// keywords are skipped, and scanning stops at the first non-keyword item
+/// after them, matching [`get_kw_value`]; a keyword with no value after it is
+/// an error.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, iter_kw_pairs};
+///
+/// let value = parse_value("(tool :a 1 :b \"x\")").unwrap();
+/// let pairs: Vec<_> = iter_kw_pairs(&value).unwrap().collect();
+/// assert_eq!(pairs[0].0, "a");
+/// assert_eq!(pairs[1].1.as_str(), Some("x"));
+/// ```
+pub fn iter_kw_pairs(root: &lexpr::Value) -> Result<impl Iterator<Item = (String, lexpr::Value)>> {
+    let list = root
+        .as_cons()
+        .ok_or_else(|| anyhow!("expected list (tool call form)"))?;
+
+    Ok(kw_pairs(list.cdr())?.into_iter())
+}
+
+/// Collect the keyword/value pairs of a keyword section starting at `start`.
+pub(crate) fn kw_pairs(start: &lexpr::Value) -> Result<Vec<(String, lexpr::Value)>> {
+    let mut pairs = Vec::new();
+    let mut cur = kw_section(start);
     while let Some(cons) = cur.as_cons() {
-        out.push(cons.car().clone());
-        cur = cons.cdr();
+        let Some(found) = normalize_kw(cons.car()) else {
+            break;
+        };
+
+        let val_cons = cons
+            .cdr()
+            .as_cons()
+            .ok_or_else(|| anyhow!("expected value after keyword :{}", found))?;
+
+        pairs.push((found.to_string(), val_cons.car().clone()));
+        cur = val_cons.cdr();
     }
 
-    Ok(out.into_iter())
+    Ok(pairs)
 }
 
-/// Parse a proper list of strings into `Vec<String>`.
+/// Collect the positional arguments of a tool-call form.
+///
+/// Positionals are the items after the head symbol up to the first keyword,
+/// so `(resize 800 600 :mode fit)` yields `800` and `600`. Only explicit
+/// keywords like `:mode` end the positional section; bare symbols such as
+/// `fit` are ordinary values here.
 ///
 /// # Example
 ///
 /// ```rust
-/// use mcp_tools::{parse_value, parse_str_list};
+/// use mcp_tools::{parse_value, positionals};
 ///
-/// let value = parse_value("(\"a\" \"b\" \"c\")").unwrap();
-/// assert_eq!(parse_str_list(&value).unwrap(), vec!["a", "b", "c"]);
+/// let value = parse_value("(resize 800 600 :mode fit)").unwrap();
+/// let args = positionals(&value).unwrap();
+/// assert_eq!(args.len(), 2);
+/// assert_eq!(args[1].as_i64(), Some(600));
 /// ```
-pub fn parse_str_list(value: &lexpr::Value) -> Result<Vec<String>> {
-    let mut out = Vec::new();
-    for item in iter_list(value)? {
-        let s = item
-            .as_str()
-            .ok_or_else(|| anyhow!("expected string item in list"))?;
-        out.push(s.to_string());
+pub fn positionals(root: &lexpr::Value) -> Result<Vec<lexpr::Value>> {
+    let list = root
+        .as_cons()
+        .ok_or_else(|| anyhow!("expected list (tool call form)"))?;
+
+    let mut values = Vec::new();
+    let mut cur = list.cdr();
+    while let Some(cons) = cur.as_cons() {
+        if is_explicit_kw(cons.car()) {
+            break;
+        }
+        values.push(cons.car().clone());
+        cur = cons.cdr();
     }
-    Ok(out)
+
+    Ok(values)
 }
 
-/// Generic representation for values that are either a literal string or a `(use "path")` reference.
+/// Extract the positional argument at `index` (0-based, after the head symbol).
 ///
-/// This is commonly used in MCP tools for specification fields that can either be
-/// inline text or a file reference.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum TextRef {
-    /// A literal string value.
-    Literal(String),
-    /// A file path reference from `(use "path")`.
-    UsePath(String),
+/// Returns `Ok(None)` when the form has fewer positionals than that.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, get_positional};
+///
+/// let value = parse_value("(resize 800 600 :mode fit)").unwrap();
+/// assert_eq!(get_positional(&value, 0).unwrap().and_then(|v| v.as_i64()), Some(800));
+/// assert!(get_positional(&value, 2).unwrap().is_none());
+/// ```
+pub fn get_positional(root: &lexpr::Value, index: usize) -> Result<Option<lexpr::Value>> {
+    Ok(positionals(root)?.into_iter().nth(index))
 }
 
-/// Parse either a string literal or `(use "path")`.
+/// Canonicalize a tool-call form by sorting its keyword arguments by name.
+///
+/// The head and positionals keep their order, and keywords are re-emitted as
+/// `lexpr` keywords, so forms that differ only in keyword order (or in
+/// writing `:a` as a keyword versus a `:`-prefixed symbol) normalize to equal
+/// values. Only the top level is reordered; nested forms are left as they are.
+///
+/// A key given more than once is an error rather than keeping either value,
+/// since tools disagree on which one wins. Positionals after the keyword
+/// section, dangling keywords and dotted lists are errors too.
 ///
 /// # Example
 ///
 /// ```rust
-/// use mcp_tools::{parse_value, parse_text_ref, TextRef};
+/// use mcp_tools::{normalize_form, parse_value};
 ///
-/// let literal = parse_value("\"hello\"").unwrap();
-/// assert_eq!(parse_text_ref(&literal).unwrap(), TextRef::Literal("hello".to_string()));
-///
-/// let use_path = parse_value("(use \"docs/spec.md\")").unwrap();
-/// assert_eq!(parse_text_ref(&use_path).unwrap(), TextRef::UsePath("docs/spec.md".to_string()));
+/// let a = parse_value("(tool \"x\" :b 2 :a 1)").unwrap();
+/// let b = parse_value("(tool \"x\" :a 1 :b 2)").unwrap();
+/// assert_eq!(normalize_form(&a).unwrap(), normalize_form(&b).unwrap());
 /// ```
-pub fn parse_text_ref(value: &lexpr::Value) -> Result<TextRef> {
-    if let Some(s) = value.as_str() {
-        return Ok(TextRef::Literal(s.to_string()));
-    }
-
-    let list = value
+pub fn normalize_form(root: &lexpr::Value) -> Result<lexpr::Value> {
+    let list = root
         .as_cons()
-        .ok_or_else(|| anyhow!("expected string or (use \"path\")"))?;
+        .ok_or_else(|| anyhow!("expected list (tool call form)"))?;
 
-    let head = list
-        .car()
-        .as_symbol()
-        .ok_or_else(|| anyhow!("expected (use \"path\")"))?;
+    let mut items = vec![list.car().clone()];
+    let mut cur = list.cdr();
+    while let Some(cons) = cur.as_cons() {
+        if is_explicit_kw(cons.car()) {
+            break;
+        }
+        items.push(cons.car().clone());
+        cur = cons.cdr();
+    }
 
-    if head != "use" {
-        return Err(anyhow!("expected (use \"path\")"));
+    let mut pairs = Vec::new();
+    while let Some(cons) = cur.as_cons() {
+        let key = Some(cons.car())
+            .filter(|k| is_explicit_kw(k))
+            .and_then(normalize_kw)
+            .ok_or_else(|| anyhow!("unexpected value {} after keyword arguments", cons.car()))?;
+        let val_cons = cons
+            .cdr()
+            .as_cons()
+            .ok_or_else(|| anyhow!("expected value after keyword :{}", key))?;
+        pairs.push((key, val_cons.car()));
+        cur = val_cons.cdr();
+    }
+    if !cur.is_null() {
+        return Err(anyhow!("expected proper list (tool call form)"));
     }
 
-    let arg_cons = list
-        .cdr()
-        .as_cons()
-        .ok_or_else(|| anyhow!("(use ...) missing argument"))?;
+    pairs.sort_by_key(|(key, _)| *key);
+    if let Some(dup) = pairs.windows(2).find(|w| w[0].0 == w[1].0) {
+        return Err(anyhow!("duplicate keyword :{}", dup[0].0));
+    }
 
-    let path = arg_cons
-        .car()
-        .as_str()
-        .ok_or_else(|| anyhow!("(use ...) path must be a string"))?;
+    for (key, value) in pairs {
+        items.push(lexpr::Value::keyword(key));
+        items.push(value.clone());
+    }
+    Ok(lexpr::Value::list(items))
+}
 
-    Ok(TextRef::UsePath(path.to_string()))
+pub(crate) fn is_explicit_kw(value: &lexpr::Value) -> bool {
+    value.as_keyword().is_some() || value.as_symbol().is_some_and(|s| s.starts_with(':'))
 }
 
-/// Render a `TextRef` back to an S-expression fragment.
+/// Extract the raw `lexpr::Value` at a nested keyword path.
+///
+/// The first key is looked up in the tool-call form; each following key is
+/// looked up in the value found so far, which may be a bare keyword list
+/// like `(:retries 3)` or a headed form like `(config :retries 3)`.
+///
+/// Returns `Ok(None)` when any key along the path is missing, and an error
+/// when an intermediate value is present but not a list.
 ///
 /// # Example
 ///
 /// ```rust
-/// use mcp_tools::{render_text_ref, TextRef};
+/// use mcp_tools::{parse_value, get_kw_path};
 ///
-/// let literal = TextRef::Literal("hello".to_string());
-/// assert_eq!(render_text_ref(&literal), "\"hello\"");
-///
-/// let use_path = TextRef::UsePath("docs/spec.md".to_string());
-/// assert_eq!(render_text_ref(&use_path), "(use \"docs/spec.md\")");
+/// let value = parse_value("(tool :config (:retries 3 :timeout 10))").unwrap();
+/// let retries = get_kw_path(&value, &["config", "retries"]).unwrap();
+/// assert_eq!(retries.and_then(|v| v.as_i64()), Some(3));
+/// assert!(get_kw_path(&value, &["config", "missing"]).unwrap().is_none());
 /// ```
-pub fn render_text_ref(value: &TextRef) -> String {
-    match value {
-        TextRef::Literal(s) => quote_str(s),
-        TextRef::UsePath(path) => format!("(use {})", quote_str(path)),
+pub fn get_kw_path(root: &lexpr::Value, path: &[&str]) -> Result<Option<lexpr::Value>> {
+    let mut current = root.clone();
+
+    for (depth, key) in path.iter().enumerate() {
+        if depth > 0 && current.is_null() {
+            return Ok(None);
+        }
+
+        let list = current.as_cons().ok_or_else(|| {
+            if depth == 0 {
+                anyhow!("expected list (tool call form)")
+            } else {
+                anyhow!(":{} must be a keyword list", path[..depth].join("."))
+            }
+        })?;
+
+        let start = if depth > 0 && is_explicit_kw(list.car()) {
+            &current
+        } else {
+            list.cdr()
+        };
+
+        let next = find_kw_value(start, key)?;
+        match next {
+            None => return Ok(None),
+            Some(v) => current = v,
+        }
     }
+
+    Ok(Some(current))
 }
 
-/// Quote and minimally escape a string for use inside an S-expression string literal.
+/// Extract a keyword argument that must be a nested form.
 ///
-/// Escaping policy:
-/// - `\` → `\\`
-/// - `"` → `\"`
-/// - `\n` → `\n` (literal backslash-n)
+/// A headed form like `:window (frame :w 800)` is returned as is. A bare
+/// keyword list like `:window (:w 800 :h 600)`, or `()`, gets the key as its
+/// head, becoming `(window :w 800 :h 600)`, so the result can be passed
+/// straight back to `get_kw_*`. Returns `Ok(None)` when the keyword is not
+/// present and an error when its value is not a list.
 ///
 /// # Example
 ///
 /// ```rust
-/// use mcp_tools::quote_str;
+/// use mcp_tools::{parse_value, get_kw_form, get_kw_int};
 ///
-/// assert_eq!(quote_str("hello"), "\"hello\"");
-/// assert_eq!(quote_str("say \"hi\""), "\"say \\\"hi\\\"\"");
+/// let value = parse_value("(tool :window (:w 800 :h 600) :name \"x\")").unwrap();
+/// let window = get_kw_form(&value, "window").unwrap().unwrap();
+/// assert_eq!(get_kw_int(&window, "w").unwrap(), Some(800));
+/// assert!(get_kw_form(&value, "name").is_err());
 /// ```
-pub fn quote_str(s: &str) -> String {
-    let mut out = String::with_capacity(s.len() + 2);
-    out.push('"');
-    for ch in s.chars() {
-        match ch {
-            '\\' => out.push_str("\\\\"),
-            '"' => out.push_str("\\\""),
-            '\n' => out.push_str("\\n"),
-            other => out.push(other),
-        }
+pub fn get_kw_form(root: &lexpr::Value, key: &str) -> Result<Option<lexpr::Value>> {
+    let Some(value) = get_kw_value(root, key)? else {
+        return Ok(None);
+    };
+
+    match value.as_cons() {
+        Some(cons) if !is_explicit_kw(cons.car()) => Ok(Some(value)),
+        Some(_) => Ok(Some(lexpr::Value::cons(lexpr::Value::symbol(key), value))),
+        None if value.is_null() => Ok(Some(lexpr::Value::cons(lexpr::Value::symbol(key), value))),
+        None => Err(anyhow!(":{} must be a list form", key)),
     }
-    out.push('"');
-    out
 }
 
-/// Render a space-separated list from already-rendered items.
+/// Extract a keyword argument as a string.
+///
+/// Returns `Ok(None)` when the keyword is not present.
 ///
 /// # Example
 ///
 /// ```rust
-/// use mcp_tools::render_list;
+/// use mcp_tools::{parse_value, get_kw_str};
 ///
-/// let items = vec!["\"a\"".to_string(), "\"b\"".to_string()];
-/// assert_eq!(render_list(items), "\"a\" \"b\"");
+/// let value = parse_value("(tool :name \"example\")").unwrap();
+/// assert_eq!(get_kw_str(&value, "name").unwrap(), Some("example".to_string()));
+/// assert_eq!(get_kw_str(&value, "missing").unwrap(), None);
 /// ```
-pub fn render_list(items: impl IntoIterator<Item = String>) -> String {
-    items.into_iter().collect::<Vec<_>>().join(" ")
+pub fn get_kw_str(root: &lexpr::Value, key: &str) -> Result<Option<String>> {
+    get_kw_value(root, key)?
+        .map(|v| str_from_value(&v, key))
+        .transpose()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn parse_value_parses() {
-        let v = parse_value("(tool :a \"b\")").unwrap();
-        assert!(v.as_cons().is_some());
-    }
-
-    #[test]
-    fn kw_extraction_string() {
-        let v = parse_value("(tool :name \"abc\")").unwrap();
-        assert_eq!(require_kw_str(&v, "name").unwrap(), "abc");
-        assert_eq!(get_kw_str(&v, "missing").unwrap(), None);
-    }
-
-    #[test]
-    fn kw_extraction_wrong_type() {
-        let v = parse_value("(tool :name (x))").unwrap();
-        assert!(get_kw_str(&v, "name").is_err());
-    }
+fn str_from_value(v: &lexpr::Value, key: &str) -> Result<String> {
+    v.as_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| anyhow!(":{} must be a string", key))
+}
+
+/// Extract a required keyword argument as a string.
+///
+/// Errors when missing.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, require_kw_str};
+///
+/// let value = parse_value("(tool :name \"example\")").unwrap();
+/// assert_eq!(require_kw_str(&value, "name").unwrap(), "example");
+/// ```
+pub fn require_kw_str(root: &lexpr::Value, key: &str) -> Result<String> {
+    get_kw_str(root, key)?.ok_or_else(|| anyhow!("missing required keyword :{}", key))
+}
+
+/// Extract a keyword argument as an integer.
+///
+/// Accepts bare integers and strings containing an integer.
+/// Returns `Ok(None)` when the keyword is not present.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, get_kw_int};
+///
+/// let value = parse_value("(tool :count 42 :name \"x\")").unwrap();
+/// assert_eq!(get_kw_int(&value, "count").unwrap(), Some(42));
+/// assert_eq!(get_kw_int(&value, "missing").unwrap(), None);
+/// assert!(get_kw_int(&value, "name").is_err());
+/// ```
+pub fn get_kw_int(root: &lexpr::Value, key: &str) -> Result<Option<i64>> {
+    get_kw_value(root, key)?
+        .map(|v| int_from_value(&v, key))
+        .transpose()
+}
+
+fn int_from_value(v: &lexpr::Value, key: &str) -> Result<i64> {
+    if let Some(n) = v.as_i64() {
+        return Ok(n);
+    }
+    if let Some(s) = v.as_str() {
+        if let Ok(n) = s.parse::<i64>() {
+            return Ok(n);
+        }
+    }
+    Err(anyhow!(":{} must be an integer", key))
+}
+
+/// Extract a keyword argument as a floating-point number.
+///
+/// Accepts bare floats, integers (coerced to `f64`), and strings containing a
+/// finite number. `NaN` and infinities written as strings are rejected.
+/// Returns `Ok(None)` when the keyword is not present.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, get_kw_f64};
+///
+/// let value = parse_value("(tool :threshold 0.75 :count 3 :name \"x\")").unwrap();
+/// assert_eq!(get_kw_f64(&value, "threshold").unwrap(), Some(0.75));
+/// assert_eq!(get_kw_f64(&value, "count").unwrap(), Some(3.0));
+/// assert!(get_kw_f64(&value, "name").is_err());
+/// ```
+pub fn get_kw_f64(root: &lexpr::Value, key: &str) -> Result<Option<f64>> {
+    get_kw_value(root, key)?
+        .map(|v| f64_from_value(&v, key))
+        .transpose()
+}
+
+/// Extract a required keyword argument as a floating-point number.
+///
+/// Errors when missing.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, require_kw_f64};
+///
+/// let value = parse_value("(tool :threshold \"1.5\")").unwrap();
+/// assert_eq!(require_kw_f64(&value, "threshold").unwrap(), 1.5);
+/// ```
+pub fn require_kw_f64(root: &lexpr::Value, key: &str) -> Result<f64> {
+    get_kw_f64(root, key)?.ok_or_else(|| anyhow!("missing required keyword :{}", key))
+}
+
+fn f64_from_value(v: &lexpr::Value, key: &str) -> Result<f64> {
+    if let Some(n) = v.as_f64() {
+        return Ok(n);
+    }
+    if let Some(n) = v.as_i64() {
+        return Ok(n as f64);
+    }
+    if let Some(s) = v.as_str() {
+        if let Ok(n) = s.trim().parse::<f64>() {
+            if n.is_finite() {
+                return Ok(n);
+            }
+        }
+    }
+    Err(anyhow!(":{} must be a finite number", key))
+}
+
+/// Extract a keyword argument as a boolean.
+///
+/// Accepts `#t`, `#f`, `true`, `false`, `"true"`, and `"false"`.
+/// Returns `Ok(None)` when the keyword is not present.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, get_kw_bool};
+///
+/// let value = parse_value("(tool :enabled #t :verbose \"false\" :name \"x\")").unwrap();
+/// assert_eq!(get_kw_bool(&value, "enabled").unwrap(), Some(true));
+/// assert_eq!(get_kw_bool(&value, "verbose").unwrap(), Some(false));
+/// assert_eq!(get_kw_bool(&value, "missing").unwrap(), None);
+/// assert!(get_kw_bool(&value, "name").is_err());
+/// ```
+pub fn get_kw_bool(root: &lexpr::Value, key: &str) -> Result<Option<bool>> {
+    get_kw_value(root, key)?
+        .map(|v| bool_from_value(&v, key))
+        .transpose()
+}
+
+fn bool_from_value(v: &lexpr::Value, key: &str) -> Result<bool> {
+    if let Some(b) = v.as_bool() {
+        return Ok(b);
+    }
+    match v.as_str().or_else(|| v.as_symbol()) {
+        Some("true") => Ok(true),
+        Some("false") => Ok(false),
+        _ => Err(anyhow!(":{} must be a boolean (true/false)", key)),
+    }
+}
+
+/// Extract a keyword argument given as a bare symbol, e.g. `:mode fast`.
+///
+/// Returns the symbol name; a keyword value like `:fast` has its leading colon
+/// stripped. Strings, numbers and lists are rejected.
+/// Returns `Ok(None)` when the keyword is not present.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, get_kw_symbol};
+///
+/// let value = parse_value("(tool :mode fast :name \"x\")").unwrap();
+/// assert_eq!(get_kw_symbol(&value, "mode").unwrap(), Some("fast".to_string()));
+/// assert_eq!(get_kw_symbol(&value, "missing").unwrap(), None);
+/// assert!(get_kw_symbol(&value, "name").is_err());
+/// ```
+pub fn get_kw_symbol(root: &lexpr::Value, key: &str) -> Result<Option<String>> {
+    get_kw_value(root, key)?
+        .map(|v| symbol_from_value(&v, key))
+        .transpose()
+}
+
+fn symbol_from_value(v: &lexpr::Value, key: &str) -> Result<String> {
+    if is_explicit_kw(v) {
+        return Ok(normalize_kw(v).unwrap_or_default().to_string());
+    }
+    v.as_symbol()
+        .map(|s| s.to_string())
+        .ok_or_else(|| anyhow!(":{} must be a symbol", key))
+}
+
+/// Extract the raw `lexpr::Value` for a keyword argument, ignoring ASCII case.
+///
+/// Behaves like [`get_kw_value`] except that `:Name` and `:NAME` both match
+/// `"name"`. Only ASCII letters are folded; other characters must match exactly.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, get_kw_value_ci};
+///
+/// let value = parse_value("(tool :Name \"example\")").unwrap();
+/// assert!(get_kw_value_ci(&value, "name").unwrap().is_some());
+/// ```
+pub fn get_kw_value_ci(root: &lexpr::Value, key: &str) -> Result<Option<lexpr::Value>> {
+    let list = root
+        .as_cons()
+        .ok_or_else(|| anyhow!("expected list (tool call form)"))?;
+
+    find_kw_value_by(list.cdr(), |found| found.eq_ignore_ascii_case(key))
+}
+
+/// Extract a keyword argument as a string, ignoring ASCII case in the keyword.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, get_kw_str_ci};
+///
+/// let value = parse_value("(tool :NAME \"example\")").unwrap();
+/// assert_eq!(get_kw_str_ci(&value, "name").unwrap(), Some("example".to_string()));
+/// ```
+pub fn get_kw_str_ci(root: &lexpr::Value, key: &str) -> Result<Option<String>> {
+    get_kw_value_ci(root, key)?
+        .map(|v| str_from_value(&v, key))
+        .transpose()
+}
+
+/// Extract a keyword argument as an integer, ignoring ASCII case in the keyword.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, get_kw_int_ci};
+///
+/// let value = parse_value("(tool :Count 3)").unwrap();
+/// assert_eq!(get_kw_int_ci(&value, "count").unwrap(), Some(3));
+/// ```
+pub fn get_kw_int_ci(root: &lexpr::Value, key: &str) -> Result<Option<i64>> {
+    get_kw_value_ci(root, key)?
+        .map(|v| int_from_value(&v, key))
+        .transpose()
+}
+
+/// Lazy iterator over the elements of a list, from [`iter_list`] or
+/// [`iter_list_lenient`].
+///
+/// Walks the cons cells as it goes and clones each element only when it is
+/// yielded, so stopping early (e.g. with `take` or `find`) skips the rest.
+#[derive(Debug, Clone)]
+pub struct ListIter<'a> {
+    cur: &'a lexpr::Value,
+}
+
+impl Iterator for ListIter<'_> {
+    type Item = lexpr::Value;
+
+    fn next(&mut self) -> Option<lexpr::Value> {
+        let cons = self.cur.as_cons()?;
+        self.cur = cons.cdr();
+        Some(cons.car().clone())
+    }
+}
+
+impl std::iter::FusedIterator for ListIter<'_> {}
+
+/// Iterate over a proper list.
+///
+/// Returns an error if `value` is not a list, or if it is an improper
+/// (dotted) list such as `(a b . c)`. The tail is checked up front without
+/// cloning anything; elements are then cloned lazily as they are yielded.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, iter_list};
+///
+/// let value = parse_value("(a b c)").unwrap();
+/// let items: Vec<_> = iter_list(&value).unwrap().collect();
+/// assert_eq!(items.len(), 3);
+///
+/// let dotted = parse_value("(a b . c)").unwrap();
+/// assert!(iter_list(&dotted).is_err());
+/// ```
+pub fn iter_list(value: &lexpr::Value) -> Result<ListIter<'_>> {
+    let mut cur = value;
+    while let Some(cons) = cur.as_cons() {
+        cur = cons.cdr();
+    }
+
+    if !(cur.is_null() || cur.is_nil()) {
+        return Err(anyhow!("expected proper list, found dotted tail {}", cur));
+    }
+
+    Ok(ListIter { cur: value })
+}
+
+/// Iterate over the elements of a list, ignoring any dotted tail.
+///
+/// Unlike [`iter_list`], this stops silently at the first non-cons cdr, so
+/// `(a b . c)` yields `a` and `b`, and a non-list value yields nothing.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, iter_list_lenient};
+///
+/// let value = parse_value("(a b . c)").unwrap();
+/// let items: Vec<_> = iter_list_lenient(&value).collect();
+/// assert_eq!(items.len(), 2);
+/// ```
+pub fn iter_list_lenient(value: &lexpr::Value) -> ListIter<'_> {
+    ListIter { cur: value }
+}
+
+/// Parse a proper list of strings into `Vec<String>`.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, parse_str_list};
+///
+/// let value = parse_value("(\"a\" \"b\" \"c\")").unwrap();
+/// assert_eq!(parse_str_list(&value).unwrap(), vec!["a", "b", "c"]);
+/// ```
+pub fn parse_str_list(value: &lexpr::Value) -> Result<Vec<String>> {
+    let mut out = Vec::new();
+    for item in iter_list(value)? {
+        let s = item
+            .as_str()
+            .ok_or_else(|| anyhow!("expected string item in list"))?;
+        out.push(s.to_string());
+    }
+    Ok(out)
+}
+
+/// Generic representation for values that are either a literal string or a `(use "path")` reference.
+///
+/// This is commonly used in MCP tools for specification fields that can either be
+/// inline text or a file reference.
+///
+/// The enum is `#[non_exhaustive]` because some variants depend on crate
+/// features (`Base64` needs `base64`), so a `match` outside this crate needs
+/// a wildcard arm to keep compiling whichever features are enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TextRef {
+    /// A literal string value.
+    Literal(String),
+    /// A file path reference from `(use "path")`, optionally `(use "path" :as "alias")`.
+    UsePath {
+        /// The referenced file path.
+        path: String,
+        /// Name given with `:as`, if any.
+        alias: Option<String>,
+    },
+    /// A line range of a file from `(use "path" :lines "start-end")`.
+    ///
+    /// Line numbers are 1-based and inclusive.
+    UsePathRange {
+        /// The referenced file path.
+        path: String,
+        /// First line of the range.
+        start: usize,
+        /// Last line of the range.
+        end: usize,
+        /// Name given with `:as`, if any.
+        alias: Option<String>,
+    },
+    /// Inline bytes from `(data "base64...")`.
+    #[cfg(feature = "base64")]
+    Base64(Vec<u8>),
+}
+
+impl TextRef {
+    /// A whole-file reference without an alias, as parsed from `(use "path")`.
+    pub fn use_path(path: impl Into<String>) -> Self {
+        TextRef::UsePath {
+            path: path.into(),
+            alias: None,
+        }
+    }
+
+    /// The `:as` alias of a file reference; `None` for literals and inline data.
+    pub fn alias(&self) -> Option<&str> {
+        match self {
+            TextRef::Literal(_) => None,
+            #[cfg(feature = "base64")]
+            TextRef::Base64(_) => None,
+            TextRef::UsePath { alias, .. } | TextRef::UsePathRange { alias, .. } => {
+                alias.as_deref()
+            }
+        }
+    }
+}
+
+/// Parse either a string literal, `(use "path")`, or `(use "path" :lines "start-end")`.
+///
+/// A `(use ...)` form may also carry `:as "alias"` to name the reference.
+/// `:lines` and `:as` are the only keywords accepted after the path; any other
+/// trailing item, or a repeated keyword, is an error so that typos such as
+/// `:line` are not silently ignored.
+///
+/// With the `base64` feature, `(data "base64...")` decodes to
+/// `TextRef::Base64`; malformed base64 is an error.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, parse_text_ref, TextRef};
+///
+/// let literal = parse_value("\"hello\"").unwrap();
+/// assert_eq!(parse_text_ref(&literal).unwrap(), TextRef::Literal("hello".to_string()));
+///
+/// let use_path = parse_value("(use \"docs/spec.md\")").unwrap();
+/// assert_eq!(parse_text_ref(&use_path).unwrap(), TextRef::use_path("docs/spec.md"));
+///
+/// let range = parse_value("(use \"docs/spec.md\" :lines \"10-40\" :as \"spec\")").unwrap();
+/// assert_eq!(
+///     parse_text_ref(&range).unwrap(),
+///     TextRef::UsePathRange {
+///         path: "docs/spec.md".to_string(),
+///         start: 10,
+///         end: 40,
+///         alias: Some("spec".to_string()),
+///     }
+/// );
+/// ```
+pub fn parse_text_ref(value: &lexpr::Value) -> Result<TextRef> {
+    if let Some(s) = value.as_str() {
+        return Ok(TextRef::Literal(s.to_string()));
+    }
+
+    let list = value
+        .as_cons()
+        .ok_or_else(|| anyhow!("expected string or (use \"path\")"))?;
+
+    let head = list
+        .car()
+        .as_symbol()
+        .ok_or_else(|| anyhow!("expected (use \"path\")"))?;
+
+    #[cfg(feature = "base64")]
+    if head == "data" {
+        return parse_data_ref(list.cdr());
+    }
+
+    if head != "use" {
+        return Err(anyhow!("expected (use \"path\")"));
+    }
+
+    let arg_cons = list
+        .cdr()
+        .as_cons()
+        .ok_or_else(|| anyhow!("(use ...) missing argument"))?;
+
+    let path = arg_cons
+        .car()
+        .as_str()
+        .ok_or_else(|| anyhow!("(use ...) path must be a string"))?;
+
+    let mut lines = None;
+    let mut alias = None;
+    let mut cur = arg_cons.cdr();
+    while let Some(cons) = cur.as_cons() {
+        let key = Some(cons.car())
+            .filter(|k| is_explicit_kw(k))
+            .and_then(normalize_kw)
+            .ok_or_else(|| anyhow!("(use ...) unexpected argument {}", cons.car()))?;
+        let value = cons
+            .cdr()
+            .as_cons()
+            .ok_or_else(|| anyhow!("(use ...) expected value after keyword :{}", key))?;
+        let slot = match key {
+            "lines" => &mut lines,
+            "as" => &mut alias,
+            other => {
+                return Err(anyhow!(
+                    "(use ...) unknown keyword :{} (expected :lines or :as)",
+                    other
+                ))
+            }
+        };
+        if slot.is_some() {
+            return Err(anyhow!("(use ...) duplicate keyword :{}", key));
+        }
+        *slot = Some(value.car());
+        cur = value.cdr();
+    }
+
+    let alias = alias
+        .map(|v| {
+            v.as_str()
+                .map(str::to_string)
+                .ok_or_else(|| anyhow!("(use ...) :as must be a string"))
+        })
+        .transpose()?;
+
+    match lines {
+        None => Ok(TextRef::UsePath {
+            path: path.to_string(),
+            alias,
+        }),
+        Some(lines) => {
+            let lines = lines
+                .as_str()
+                .ok_or_else(|| anyhow!("(use ...) :lines must be a string like \"10-40\""))?;
+            let (start, end) = parse_line_range(lines)?;
+            Ok(TextRef::UsePathRange {
+                path: path.to_string(),
+                start,
+                end,
+                alias,
+            })
+        }
+    }
+}
+
+#[cfg(feature = "base64")]
+fn parse_data_ref(args: &lexpr::Value) -> Result<TextRef> {
+    use base64::Engine;
+
+    let arg_cons = args
+        .as_cons()
+        .ok_or_else(|| anyhow!("(data ...) missing argument"))?;
+    let encoded = arg_cons
+        .car()
+        .as_str()
+        .ok_or_else(|| anyhow!("(data ...) payload must be a base64 string"))?;
+    if !arg_cons.cdr().is_null() {
+        return Err(anyhow!("(data ...) takes a single string argument"));
+    }
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| anyhow!("(data ...) payload is not valid base64: {}", e))?;
+    Ok(TextRef::Base64(bytes))
+}
+
+fn parse_line_range(s: &str) -> Result<(usize, usize)> {
+    let (start, end) = s
+        .split_once('-')
+        .ok_or_else(|| anyhow!("(use ...) :lines must look like \"start-end\", got {:?}", s))?;
+    let start: usize = start
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("(use ...) :lines start must be a number, got {:?}", start))?;
+    let end: usize = end
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("(use ...) :lines end must be a number, got {:?}", end))?;
+    if start == 0 {
+        return Err(anyhow!("(use ...) :lines are 1-based, got start 0"));
+    }
+    if start > end {
+        return Err(anyhow!(
+            "(use ...) :lines range is reversed: {} > {}",
+            start,
+            end
+        ));
+    }
+    Ok((start, end))
+}
+
+/// Render a `TextRef` back to an S-expression fragment.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{render_text_ref, TextRef};
+///
+/// let literal = TextRef::Literal("hello".to_string());
+/// assert_eq!(render_text_ref(&literal), "\"hello\"");
+///
+/// let use_path = TextRef::use_path("docs/spec.md");
+/// assert_eq!(render_text_ref(&use_path), "(use \"docs/spec.md\")");
+///
+/// let range = TextRef::UsePathRange {
+///     path: "a.md".to_string(),
+///     start: 1,
+///     end: 5,
+///     alias: Some("intro".to_string()),
+/// };
+/// assert_eq!(render_text_ref(&range), "(use \"a.md\" :lines \"1-5\" :as \"intro\")");
+/// ```
+pub fn render_text_ref(value: &TextRef) -> String {
+    let (path, lines, alias) = match value {
+        TextRef::Literal(s) => return quote_str(s),
+        #[cfg(feature = "base64")]
+        TextRef::Base64(bytes) => {
+            use base64::Engine;
+            let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+            return format!("(data {})", quote_str(&encoded));
+        }
+        TextRef::UsePath { path, alias } => (path, None, alias),
+        TextRef::UsePathRange {
+            path,
+            start,
+            end,
+            alias,
+        } => (path, Some(format!("{}-{}", start, end)), alias),
+    };
+
+    let mut out = format!("(use {}", quote_str(path));
+    if let Some(lines) = lines {
+        out.push_str(&format!(" :lines {}", quote_str(&lines)));
+    }
+    if let Some(alias) = alias {
+        out.push_str(&format!(" :as {}", quote_str(alias)));
+    }
+    out.push(')');
+    out
+}
+
+/// Resolve a `TextRef` to its text content.
+///
+/// `Literal` values are returned as-is and `Base64` data is decoded as UTF-8.
+/// `UsePath` and `UsePathRange` are read from a file relative to `base`; for a
+/// range, only lines `start..=end` are returned (lines past the end of the
+/// file are ignored).
+///
+/// Absolute paths and `..` components that would escape `base` are rejected
+/// so that a tool call cannot read arbitrary files.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{resolve_text_ref, TextRef};
+/// use std::path::Path;
+///
+/// let text = resolve_text_ref(&TextRef::Literal("inline".to_string()), Path::new(".")).unwrap();
+/// assert_eq!(text, "inline");
+///
+/// let escape = TextRef::use_path("../secret.txt");
+/// assert!(resolve_text_ref(&escape, Path::new("docs")).is_err());
+/// ```
+pub fn resolve_text_ref(value: &TextRef, base: &Path) -> Result<String> {
+    match value {
+        TextRef::Literal(s) => Ok(s.clone()),
+        #[cfg(feature = "base64")]
+        TextRef::Base64(bytes) => String::from_utf8(bytes.clone())
+            .map_err(|_| anyhow!("(data ...) payload is not valid UTF-8 text")),
+        TextRef::UsePath { path, .. } => {
+            let full = join_within_base(base, path)?;
+            std::fs::read_to_string(&full)
+                .with_context(|| format!("failed to read (use {})", quote_str(path)))
+        }
+        TextRef::UsePathRange {
+            path, start, end, ..
+        } => {
+            let full = join_within_base(base, path)?;
+            let content = std::fs::read_to_string(&full)
+                .with_context(|| format!("failed to read (use {})", quote_str(path)))?;
+            let first = (*start).max(1);
+            let lines: Vec<&str> = content
+                .lines()
+                .skip(first - 1)
+                .take((end + 1).saturating_sub(first))
+                .collect();
+            Ok(lines.join("\n"))
+        }
+    }
+}
+
+fn join_within_base(base: &Path, path: &str) -> Result<PathBuf> {
+    let rel = Path::new(path);
+    let mut depth = 0usize;
+    for component in rel.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => {
+                depth = depth.checked_sub(1).ok_or_else(|| {
+                    anyhow!("(use {}) escapes the base directory", quote_str(path))
+                })?;
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(anyhow!("(use {}) must be a relative path", quote_str(path)));
+            }
+        }
+    }
+    Ok(base.join(rel))
+}
+
+/// Quote and minimally escape a string for use inside an S-expression string literal.
+///
+/// Escaping policy:
+/// - `\` → `\\`
+/// - `"` → `\"`
+/// - `\n` → `\n` (literal backslash-n)
+/// - tab → `\t`, carriage return → `\r`
+/// - other C0 control characters and DEL → `\xNN;` (R7RS hex escape)
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::quote_str;
+///
+/// assert_eq!(quote_str("hello"), "\"hello\"");
+/// assert_eq!(quote_str("say \"hi\""), "\"say \\\"hi\\\"\"");
+/// ```
+pub fn quote_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_ascii_control() => out.push_str(&format!("\\x{:02x};", c as u32)),
+            other => out.push(other),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Quote a string so that [`parse_value`] reads back exactly the same text.
+///
+/// Only `\` and `"` are escaped; every other character, including newlines,
+/// tabs and other control characters, is written through literally. Any
+/// S-expression reader keeps raw characters inside a string literal as-is,
+/// so the round trip does not depend on which escape sequences the reader
+/// understands. The price is that the output may span several lines; use
+/// [`quote_str`] when the literal must stay on one line.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, quote_str_exact};
+///
+/// let s = "line one\nline \"two\"";
+/// assert_eq!(quote_str_exact(s), "\"line one\nline \\\"two\\\"\"");
+/// assert_eq!(parse_value(&quote_str_exact(s)).unwrap().as_str(), Some(s));
+/// ```
+pub fn quote_str_exact(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            other => out.push(other),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Render a symbol name, bar-quoting it when it can't be written bare.
+///
+/// Names that [`parse_value`] reads back as the same symbol (including
+/// non-ASCII ones like `café`) are emitted as-is. Anything else (spaces,
+/// parentheses, `;`, the empty name, a lone `.`, names that read as numbers)
+/// is written as `|...|` with `|` and `\` escaped by a backslash.
+///
+/// The `lexpr` reader does not understand `|...|`, so bar-quoted symbols
+/// keep their name visible to other readers but do not round-trip through
+/// [`parse_value`].
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::quote_symbol;
+///
+/// assert_eq!(quote_symbol("success"), "success");
+/// assert_eq!(quote_symbol("café"), "café");
+/// assert_eq!(quote_symbol("two words"), "|two words|");
+/// ```
+pub fn quote_symbol(s: &str) -> String {
+    if is_plain_symbol(s) {
+        return s.to_string();
+    }
+
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('|');
+    for ch in s.chars() {
+        if ch == '|' || ch == '\\' {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out.push('|');
+    out
+}
+
+/// Whether `s` reads back as the same symbol when written without bars.
+pub(crate) fn is_plain_symbol(s: &str) -> bool {
+    matches!(lexpr::from_str(s), Ok(lexpr::Value::Symbol(read)) if &*read == s)
+}
+
+/// Decode a quoted S-expression string literal back into its payload.
+///
+/// This is the inverse of [`quote_str`]: the input must begin and end with
+/// `"`, and the escapes `\\`, `\"`, `\n`, `\t`, `\r`, and `\xNN;` are decoded.
+/// A hex escape takes one to six hex digits and must end with `;`.
+/// Errors on missing quotes, a dangling backslash, an unescaped `"`, or an
+/// unknown escape sequence.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{quote_str, unquote_str};
+///
+/// assert_eq!(unquote_str("\"say \\\"hi\\\"\"").unwrap(), "say \"hi\"");
+/// assert_eq!(unquote_str(&quote_str("a\tb\n")).unwrap(), "a\tb\n");
+/// assert!(unquote_str("no quotes").is_err());
+/// ```
+pub fn unquote_str(s: &str) -> Result<String> {
+    let inner = s
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .ok_or_else(|| anyhow!("expected string literal surrounded by double quotes"))?;
+
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                let esc = chars
+                    .next()
+                    .ok_or_else(|| anyhow!("dangling backslash at end of string literal"))?;
+                match esc {
+                    '\\' => out.push('\\'),
+                    '"' => out.push('"'),
+                    'n' => out.push('\n'),
+                    't' => out.push('\t'),
+                    'r' => out.push('\r'),
+                    'x' => {
+                        let mut hex = String::new();
+                        loop {
+                            match chars.next() {
+                                Some(';') => break,
+                                Some(c) if c.is_ascii_hexdigit() && hex.len() < 6 => hex.push(c),
+                                _ => {
+                                    return Err(anyhow!(
+                                        "unterminated hex escape \\x{} (expected up to 6 hex digits and ';')",
+                                        hex
+                                    ))
+                                }
+                            }
+                        }
+                        let code = u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| anyhow!("invalid hex escape \\x{};", hex))?;
+                        out.push(code);
+                    }
+                    other => return Err(anyhow!("unknown escape sequence \\{}", other)),
+                }
+            }
+            '"' => return Err(anyhow!("unescaped double quote inside string literal")),
+            other => out.push(other),
+        }
+    }
+
+    Ok(out)
+}
+
+/// Render a space-separated list from already-rendered items.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::render_list;
+///
+/// let items = vec!["\"a\"".to_string(), "\"b\"".to_string()];
+/// assert_eq!(render_list(items), "\"a\" \"b\"");
+/// ```
+pub fn render_list(items: impl IntoIterator<Item = String>) -> String {
+    items.into_iter().collect::<Vec<_>>().join(" ")
+}
+
+/// Render an arbitrary `lexpr::Value` back to an S-expression string.
+///
+/// Strings are rendered with [`quote_str`], symbols with [`quote_symbol`],
+/// booleans as `#t`/`#f`, keywords as `:name`, and lists recursively (including dotted tails). Any other
+/// atom falls back to `lexpr`'s own printer.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_tools::{parse_value, render_value};
+///
+/// let value = parse_value("(tool :name \"x\" :items (1 2) :on #t)").unwrap();
+/// assert_eq!(render_value(&value), "(tool :name \"x\" :items (1 2) :on #t)");
+/// ```
+pub fn render_value(value: &lexpr::Value) -> String {
+    match value {
+        lexpr::Value::String(s) => quote_str(s),
+        lexpr::Value::Symbol(s) => quote_symbol(s),
+        lexpr::Value::Keyword(k) => format!(":{}", k),
+        lexpr::Value::Number(n) => n.to_string(),
+        lexpr::Value::Bool(true) => "#t".to_string(),
+        lexpr::Value::Bool(false) => "#f".to_string(),
+        lexpr::Value::Null => "()".to_string(),
+        lexpr::Value::Cons(_) => {
+            let mut items = Vec::new();
+            let mut cur = value;
+            while let Some(cons) = cur.as_cons() {
+                items.push(render_value(cons.car()));
+                cur = cons.cdr();
+            }
+            if cur.is_null() {
+                format!("({})", render_list(items))
+            } else {
+                format!("({} . {})", render_list(items), render_value(cur))
+            }
+        }
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_value_parses() {
+        let v = parse_value("(tool :a \"b\")").unwrap();
+        assert!(v.as_cons().is_some());
+    }
+
+    #[test]
+    fn parse_values_multiple_forms() {
+        let vs = parse_values("(a :x 1)  \n\t(b :y \"z\")\n").unwrap();
+        assert_eq!(vs.len(), 2);
+        assert_eq!(require_kw_str(&vs[1], "y").unwrap(), "z");
+
+        assert!(parse_values("").unwrap().is_empty());
+        assert!(parse_values(" \n\t ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_values_reports_offset() {
+        let err = parse_values("(a :x 1)\n(b :y").unwrap_err();
+        assert!(err.to_string().contains("byte offset"));
+    }
+
+    #[test]
+    fn for_each_value_stops_on_callback_error() {
+        let mut seen = Vec::new();
+        let err = for_each_value("(a) (b) (c)", |v| {
+            seen.push(v);
+            Err(anyhow!("stop"))
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "stop");
+        assert_eq!(seen, vec![parse_value("(a)").unwrap()]);
+    }
+
+    #[test]
+    fn for_each_value_reports_offset_mid_stream() {
+        let mut count = 0;
+        let err = for_each_value("(a :x 1)\n(b :y 2)\n(c :z", |_| {
+            count += 1;
+            Ok(())
+        })
+        .unwrap_err();
+        assert_eq!(count, 2);
+        assert!(err.to_string().contains("byte offset"));
+    }
+
+    #[test]
+    fn kw_extraction_string() {
+        let v = parse_value("(tool :name \"abc\")").unwrap();
+        assert_eq!(require_kw_str(&v, "name").unwrap(), "abc");
+        assert_eq!(get_kw_str(&v, "missing").unwrap(), None);
+    }
+
+    #[test]
+    fn kw_extraction_dangling_keyword() {
+        let v = parse_value("(tool :a \"x\" :b)").unwrap();
+        for key in ["a", "b"] {
+            let err = get_kw_value(&v, key).unwrap_err();
+            assert!(err.to_string().contains(":b"), "{}: {}", key, err);
+        }
+        assert!(get_kw_str_ci(&v, "A").is_err());
+    }
+
+    #[test]
+    fn kw_extraction_wrong_type() {
+        let v = parse_value("(tool :name (x))").unwrap();
+        assert!(get_kw_str(&v, "name").is_err());
+    }
+
+    #[test]
+    fn kw_extraction_int_and_bool() {
+        let v = parse_value("(tool :n 7 :s \"12\" :t #t :f false :bad (x))").unwrap();
+        assert_eq!(get_kw_int(&v, "n").unwrap(), Some(7));
+        assert_eq!(get_kw_int(&v, "s").unwrap(), Some(12));
+        assert_eq!(get_kw_bool(&v, "t").unwrap(), Some(true));
+        assert_eq!(get_kw_bool(&v, "f").unwrap(), Some(false));
+        assert!(get_kw_int(&v, "bad").is_err());
+        assert!(get_kw_bool(&v, "bad").is_err());
+        assert!(get_kw_bool(&v, "n").is_err());
+    }
+
+    #[test]
+    fn kw_extraction_symbol() {
+        let v = parse_value("(tool :mode fast :level :high :name \"x\" :l (a))").unwrap();
+        assert_eq!(get_kw_symbol(&v, "mode").unwrap(), Some("fast".to_string()));
+        assert_eq!(
+            get_kw_symbol(&v, "level").unwrap(),
+            Some("high".to_string())
+        );
+        assert_eq!(get_kw_symbol(&v, "missing").unwrap(), None);
+        assert!(get_kw_symbol(&v, "name").is_err());
+        assert!(get_kw_symbol(&v, "l").is_err());
+    }
+
+    #[test]
+    fn kw_path_two_and_three_levels() {
+        let v = parse_value("(tool :config (:retries 3 :net (:timeout 10)))").unwrap();
+        let retries = get_kw_path(&v, &["config", "retries"]).unwrap().unwrap();
+        assert_eq!(retries.as_i64(), Some(3));
+        let timeout = get_kw_path(&v, &["config", "net", "timeout"]).unwrap().unwrap();
+        assert_eq!(timeout.as_i64(), Some(10));
+        let net = get_kw_path(&v, &["config", "net"]).unwrap().unwrap();
+        assert!(net.as_cons().is_some());
+    }
+
+    #[test]
+    fn kw_path_headed_form() {
+        let v = parse_value("(tool :config (config :retries 3))").unwrap();
+        let retries = get_kw_path(&v, &["config", "retries"]).unwrap().unwrap();
+        assert_eq!(retries.as_i64(), Some(3));
+    }
+
+    #[test]
+    fn kw_path_missing_and_non_list() {
+        let v = parse_value("(tool :config (:retries 3) :name \"x\")").unwrap();
+        assert!(get_kw_path(&v, &["other", "retries"]).unwrap().is_none());
+        assert!(get_kw_path(&v, &["config", "missing", "deeper"]).unwrap().is_none());
+        assert!(get_kw_path(&v, &["name", "retries"]).is_err());
+    }
+
+    #[test]
+    fn kw_form_nested_scalar_and_missing() {
+        let v = parse_value(
+            "(tool :window (:w 800 :h 600) :frame (frame :title \"t\") :empty () :name \"x\")",
+        )
+        .unwrap();
+
+        let window = get_kw_form(&v, "window").unwrap().unwrap();
+        assert_eq!(window, parse_value("(window :w 800 :h 600)").unwrap());
+        assert_eq!(get_kw_int(&window, "h").unwrap(), Some(600));
+
+        let frame = get_kw_form(&v, "frame").unwrap().unwrap();
+        assert_eq!(require_kw_str(&frame, "title").unwrap(), "t");
+
+        let empty = get_kw_form(&v, "empty").unwrap().unwrap();
+        assert_eq!(get_kw_value(&empty, "w").unwrap(), None);
+
+        let err = get_kw_form(&v, "name").unwrap_err();
+        assert_eq!(err.to_string(), ":name must be a list form");
+        assert!(get_kw_form(&v, "missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn iter_list_proper_and_dotted() {
+        let proper = parse_value("(a b c)").unwrap();
+        assert_eq!(iter_list(&proper).unwrap().count(), 3);
+
+        let empty = parse_value("()").unwrap();
+        assert_eq!(iter_list(&empty).unwrap().count(), 0);
+
+        let dotted = parse_value("(a b . c)").unwrap();
+        assert!(iter_list(&dotted).is_err());
+    }
+
+    #[test]
+    fn iter_list_is_lazy() {
+        let long = lexpr::Value::list((0..10_000i64).map(lexpr::Value::from));
+
+        // `lexpr::Value` can't count its clones, so check laziness through
+        // the iterator state instead: after one step the rest is still unread.
+        let mut iter = iter_list(&long).unwrap();
+        assert_eq!(iter.next().and_then(|v| v.as_i64()), Some(0));
+        assert_eq!(iter.clone().next().and_then(|v| v.as_i64()), Some(1));
+        assert_eq!(iter.count(), 9_999);
+
+        let first: Vec<_> = iter_list(&long).unwrap().take(1).collect();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].as_i64(), Some(0));
+
+        let dotted = parse_value("(a b . c)").unwrap();
+        let mut lenient = iter_list_lenient(&dotted);
+        assert!(lenient.nth(1).is_some());
+        assert!(lenient.next().is_none());
+        assert!(lenient.next().is_none());
+    }
+
+    #[test]
+    fn iter_list_lenient_truncates() {
+        let proper = parse_value("(a b c)").unwrap();
+        assert_eq!(iter_list_lenient(&proper).count(), 3);
+
+        let empty = parse_value("()").unwrap();
+        assert_eq!(iter_list_lenient(&empty).count(), 0);
+
+        let dotted = parse_value("(a b . c)").unwrap();
+        let items: Vec<_> = iter_list_lenient(&dotted).collect();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].as_symbol(), Some("b"));
+    }
+
+    #[test]
+    fn kw_extraction_case_insensitive() {
+        let v = parse_value("(tool :Name \"a\" :COUNT 2 :Über \"u\")").unwrap();
+        assert_eq!(get_kw_str_ci(&v, "name").unwrap(), Some("a".to_string()));
+        assert_eq!(get_kw_int_ci(&v, "count").unwrap(), Some(2));
+        assert_eq!(get_kw_str(&v, "name").unwrap(), None);
+
+        // Only ASCII letters are folded.
+        assert!(get_kw_value_ci(&v, "Über").unwrap().is_some());
+        assert!(get_kw_value_ci(&v, "über").unwrap().is_none());
+        assert!(get_kw_value_ci(&v, "ÜBER").unwrap().is_some());
+    }
+
+    #[test]
+    fn list_kw_keys_in_order() {
+        let v = parse_value("(tool :a 1 :b 2 :a 3)").unwrap();
+        assert_eq!(list_kw_keys(&v).unwrap(), vec!["a", "b", "a"]);
+
+        let v = parse_value("(tool :a 1 :b 2 \"positional\" :c 3)").unwrap();
+        assert_eq!(list_kw_keys(&v).unwrap(), vec!["a", "b"]);
+
+        let v = parse_value("(tool)").unwrap();
+        assert!(list_kw_keys(&v).unwrap().is_empty());
+    }
+
+    #[test]
+    fn positionals_before_keywords() {
+        let v = parse_value("(resize 800 600 :mode fit)").unwrap();
+        let args = positionals(&v).unwrap();
+        assert_eq!(args.len(), 2);
+        assert_eq!(args[0].as_i64(), Some(800));
+        assert_eq!(args[1].as_i64(), Some(600));
+        assert_eq!(get_positional(&v, 1).unwrap().unwrap().as_i64(), Some(600));
+        assert!(get_positional(&v, 2).unwrap().is_none());
+        assert_eq!(
+            get_kw_value(&v, "mode").unwrap().unwrap().as_symbol(),
+            Some("fit")
+        );
+        assert_eq!(list_kw_keys(&v).unwrap(), vec!["mode"]);
+    }
+
+    #[test]
+    fn kw_lookup_keeps_bare_keys_before_positionals() {
+        let v = parse_value("(tool name \"x\" :mode fit)").unwrap();
+        assert_eq!(get_kw_str(&v, "name").unwrap().as_deref(), Some("x"));
+        assert_eq!(
+            get_kw_value(&v, "mode").unwrap().unwrap().as_symbol(),
+            Some("fit")
+        );
+        assert_eq!(list_kw_keys(&v).unwrap(), vec!["name", "mode"]);
+
+        let keyword_value = parse_value("(tool name :auto)").unwrap();
+        let auto = get_kw_value(&keyword_value, "name").unwrap().unwrap();
+        assert_eq!(normalize_kw(&auto), Some("auto"));
+        let unique = get_kw_value_unique(&keyword_value, "name").unwrap();
+        assert_eq!(unique, Some(auto));
+    }
+
+    #[test]
+    fn positionals_empty_when_keywords_first() {
+        let v = parse_value("(tool :name \"x\")").unwrap();
+        assert!(positionals(&v).unwrap().is_empty());
+        assert!(get_positional(&v, 0).unwrap().is_none());
+
+        let bare = parse_value("(tool)").unwrap();
+        assert!(positionals(&bare).unwrap().is_empty());
+    }
+
+    #[test]
+    fn normalize_form_sorts_keywords() {
+        let a = parse_value("(tool 1 \"x\" :b 2 :c (3) :a \"one\")").unwrap();
+        let b = parse_value("(tool 1 \"x\" :a \"one\" :c (3) :b 2)").unwrap();
+        let normalized = normalize_form(&a).unwrap();
+        assert_eq!(normalized, normalize_form(&b).unwrap());
+        assert_eq!(
+            render_value(&normalized),
+            "(tool 1 \"x\" :a \"one\" :b 2 :c (3))"
+        );
+
+        let positional_order = parse_value("(tool \"x\" 1 :a \"one\")").unwrap();
+        assert_ne!(
+            normalize_form(&positional_order).unwrap(),
+            normalize_form(&parse_value("(tool 1 \"x\" :a \"one\")").unwrap()).unwrap()
+        );
+
+        let bare = parse_value("(tool)").unwrap();
+        assert_eq!(normalize_form(&bare).unwrap(), bare);
+    }
+
+    #[test]
+    fn normalize_form_rejects_duplicates() {
+        let v = parse_value("(tool :b 1 :a 2 :b 3)").unwrap();
+        let err = normalize_form(&v).unwrap_err();
+        assert!(err.to_string().contains("duplicate keyword :b"));
+
+        let trailing = parse_value("(tool :a 1 \"x\")").unwrap();
+        assert!(normalize_form(&trailing).is_err());
+
+        let dangling = parse_value("(tool :a 1 :b)").unwrap();
+        assert!(normalize_form(&dangling).is_err());
+    }
+
+    #[test]
+    fn iter_kw_pairs_yields_pairs() {
+        let v = parse_value("(tool :a 1 :b \"x\")").unwrap();
+        let pairs: Vec<_> = iter_kw_pairs(&v).unwrap().collect();
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[0].0, "a");
+        assert_eq!(pairs[0].1.as_i64(), Some(1));
+        assert_eq!(pairs[1].0, "b");
+        assert_eq!(pairs[1].1.as_str(), Some("x"));
+    }
+
+    #[test]
+    fn iter_kw_pairs_empty_and_dangling() {
+        let empty = parse_value("(tool)").unwrap();
+        assert_eq!(iter_kw_pairs(&empty).unwrap().count(), 0);
+
+        // Positionals before the keywords are skipped
+        let positional = parse_value("(tool \"x\" :a 1)").unwrap();
+        let pairs: Vec<_> = iter_kw_pairs(&positional).unwrap().collect();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].0, "a");
+
+        let dangling = parse_value("(tool :a 1 :b)").unwrap();
+        let err = iter_kw_pairs(&dangling).err().unwrap();
+        assert!(err.to_string().contains(":b"));
+    }
+
+    #[test]
+    fn kw_unique_detects_duplicates() {
+        let v = parse_value("(tool :name \"a\")").unwrap();
+        assert_eq!(require_kw_str_unique(&v, "name").unwrap(), "a");
+
+        let v = parse_value("(tool :name \"a\" :name \"b\")").unwrap();
+        let err = require_kw_str_unique(&v, "name").unwrap_err();
+        assert!(err.to_string().contains(":name"));
+
+        let v = parse_value("(tool :a 1 :b 2 :c 3 :b 4)").unwrap();
+        assert!(get_kw_value_unique(&v, "a").unwrap().is_some());
+        assert!(get_kw_value_unique(&v, "c").unwrap().is_some());
+        assert!(get_kw_value_unique(&v, "b").is_err());
+        assert!(get_kw_value_unique(&v, "missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn kw_extraction_f64() {
+        let v =
+            parse_value("(tool :a 0.75 :b 3 :c \"1.5\" :d fast :e \"NaN\" :f \"inf\")").unwrap();
+        assert_eq!(get_kw_f64(&v, "a").unwrap(), Some(0.75));
+        assert_eq!(get_kw_f64(&v, "b").unwrap(), Some(3.0));
+        assert_eq!(get_kw_f64(&v, "c").unwrap(), Some(1.5));
+        assert_eq!(get_kw_f64(&v, "missing").unwrap(), None);
+        assert!(get_kw_f64(&v, "d").is_err());
+        assert!(get_kw_f64(&v, "e").is_err());
+        assert!(get_kw_f64(&v, "f").is_err());
+        assert!(require_kw_f64(&v, "missing").is_err());
+    }
 
     #[test]
     fn parse_str_list_ok() {
@@ -384,17 +1871,275 @@ mod tests {
         let usev = parse_value("(use \"docs/spec.md\")").unwrap();
         assert_eq!(
             parse_text_ref(&usev).unwrap(),
-            TextRef::UsePath("docs/spec.md".to_string())
+            TextRef::use_path("docs/spec.md")
         );
 
-        let rendered = render_text_ref(&TextRef::UsePath("x".to_string()));
+        let rendered = render_text_ref(&TextRef::use_path("x"));
         assert_eq!(rendered, "(use \"x\")");
     }
 
+    #[test]
+    fn text_ref_line_range() {
+        let v = parse_value("(use \"docs/spec.md\" :lines \"10-40\")").unwrap();
+        let parsed = parse_text_ref(&v).unwrap();
+        assert_eq!(
+            parsed,
+            TextRef::UsePathRange {
+                path: "docs/spec.md".to_string(),
+                start: 10,
+                end: 40,
+                alias: None,
+            }
+        );
+
+        let rendered = render_text_ref(&parsed);
+        assert_eq!(rendered, "(use \"docs/spec.md\" :lines \"10-40\")");
+        assert_eq!(
+            parse_text_ref(&parse_value(&rendered).unwrap()).unwrap(),
+            parsed
+        );
+    }
+
+    #[test]
+    fn text_ref_line_range_errors() {
+        let reversed = parse_value("(use \"a.md\" :lines \"40-10\")").unwrap();
+        assert!(parse_text_ref(&reversed).is_err());
+
+        let non_numeric = parse_value("(use \"a.md\" :lines \"a-b\")").unwrap();
+        assert!(parse_text_ref(&non_numeric).is_err());
+
+        let no_dash = parse_value("(use \"a.md\" :lines \"10\")").unwrap();
+        assert!(parse_text_ref(&no_dash).is_err());
+
+        let zero = parse_value("(use \"a.md\" :lines \"0-5\")").unwrap();
+        let err = parse_text_ref(&zero).unwrap_err();
+        assert!(err.to_string().contains("1-based"));
+    }
+
+    #[test]
+    fn unquote_str_round_trip() {
+        let inputs = [
+            "",
+            "plain",
+            "a\"b",
+            "back\\slash",
+            "multi\nline\ttab\rcr",
+            "\u{1}ctl",
+            "ünï",
+        ];
+        for input in inputs {
+            assert_eq!(unquote_str(&quote_str(input)).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn unquote_str_errors() {
+        assert!(unquote_str("abc").is_err());
+        assert!(unquote_str("\"abc").is_err());
+        assert!(unquote_str("\"abc\\\"").is_err());
+        assert!(unquote_str("\"a\"b\"").is_err());
+        assert!(unquote_str("\"\\q\"").is_err());
+
+        assert_eq!(unquote_str("\"\\x41;\"").unwrap(), "A");
+        assert!(unquote_str("\"\\x41\"").is_err());
+        assert!(unquote_str("\"\\x41abc\"").is_err());
+        assert!(unquote_str("\"\\x0000041;\"").is_err());
+        assert!(unquote_str("\"\\x;\"").is_err());
+    }
+
+    #[test]
+    fn render_value_round_trip() {
+        let inputs = [
+            "(tool :name \"a \\\"q\\\" b\" :n 42 :on #t :off #f)",
+            "(outer (inner (deep \"x\")) () (a . b))",
+            "\"line\\nbreak\"",
+        ];
+        for input in inputs {
+            let v = parse_value(input).unwrap();
+            let rendered = render_value(&v);
+            assert_eq!(parse_value(&rendered).unwrap(), v, "round-trip of {}", input);
+        }
+    }
+
+    #[test]
+    fn text_ref_alias() {
+        let v = parse_value("(use \"docs/spec.md\" :as \"spec\")").unwrap();
+        let parsed = parse_text_ref(&v).unwrap();
+        assert_eq!(
+            parsed,
+            TextRef::UsePath {
+                path: "docs/spec.md".to_string(),
+                alias: Some("spec".to_string()),
+            }
+        );
+        assert_eq!(parsed.alias(), Some("spec"));
+        assert_eq!(TextRef::use_path("a.md").alias(), None);
+
+        let rendered = render_text_ref(&parsed);
+        assert_eq!(rendered, "(use \"docs/spec.md\" :as \"spec\")");
+        assert_eq!(
+            parse_text_ref(&parse_value(&rendered).unwrap()).unwrap(),
+            parsed
+        );
+
+        let ranged = parse_value("(use \"a.md\" :as \"intro\" :lines \"1-5\")").unwrap();
+        let parsed = parse_text_ref(&ranged).unwrap();
+        assert_eq!(parsed.alias(), Some("intro"));
+        let rendered = render_text_ref(&parsed);
+        assert_eq!(rendered, "(use \"a.md\" :lines \"1-5\" :as \"intro\")");
+        assert_eq!(
+            parse_text_ref(&parse_value(&rendered).unwrap()).unwrap(),
+            parsed
+        );
+    }
+
+    #[test]
+    fn text_ref_rejects_unknown_trailing_items() {
+        let unknown = parse_value("(use \"a.md\" :line \"1-5\")").unwrap();
+        let err = parse_text_ref(&unknown).unwrap_err();
+        assert!(err.to_string().contains(":line"));
+
+        let duplicate = parse_value("(use \"a.md\" :as \"x\" :as \"y\")").unwrap();
+        assert!(parse_text_ref(&duplicate).is_err());
+
+        let extra = parse_value("(use \"a.md\" \"b.md\")").unwrap();
+        assert!(parse_text_ref(&extra).is_err());
+
+        let non_string = parse_value("(use \"a.md\" :as spec)").unwrap();
+        assert!(parse_text_ref(&non_string).is_err());
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn text_ref_base64_decodes() {
+        let v = parse_value("(data \"aGVsbG8AAf8=\")").unwrap();
+        let parsed = parse_text_ref(&v).unwrap();
+        assert_eq!(parsed, TextRef::Base64(b"hello\x00\x01\xff".to_vec()));
+        assert_eq!(parsed.alias(), None);
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn text_ref_base64_round_trip() {
+        let original = TextRef::Base64(vec![0, 1, 2, 250, 255]);
+        let rendered = render_text_ref(&original);
+        assert_eq!(rendered, "(data \"AAEC+v8=\")");
+        let reparsed = parse_text_ref(&parse_value(&rendered).unwrap()).unwrap();
+        assert_eq!(reparsed, original);
+
+        let text = TextRef::Base64(b"inline".to_vec());
+        assert_eq!(resolve_text_ref(&text, Path::new(".")).unwrap(), "inline");
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn text_ref_base64_invalid() {
+        let v = parse_value("(data \"not base64!\")").unwrap();
+        let err = parse_text_ref(&v).unwrap_err();
+        assert!(err.to_string().contains("not valid base64"));
+
+        let v = parse_value("(data 42)").unwrap();
+        assert!(parse_text_ref(&v).is_err());
+    }
+
+    #[test]
+    fn render_value_nested() {
+        let v = parse_value("(a (b (c \"d\")) 1)").unwrap();
+        assert_eq!(render_value(&v), "(a (b (c \"d\")) 1)");
+    }
+
+    #[test]
+    fn quote_symbol_plain_and_barred() {
+        for plain in [
+            "success", "my-tool", ":name", "a/b", "+", "-", "...", "->x", "café", "a#b",
+        ] {
+            assert_eq!(quote_symbol(plain), plain);
+            let rendered = render_value(&lexpr::Value::list(vec![lexpr::Value::symbol(plain)]));
+            let reparsed = parse_value(&rendered).unwrap();
+            assert_eq!(reparsed.as_cons().unwrap().car().as_symbol(), Some(plain));
+        }
+
+        assert_eq!(quote_symbol("two words"), "|two words|");
+        assert_eq!(quote_symbol("a|b c"), "|a\\|b c|");
+        assert_eq!(quote_symbol("back\\ slash"), "|back\\\\ slash|");
+        for needs_bars in ["", ".", "42", "-1", "(x)", "a;b", "é"] {
+            assert!(
+                quote_symbol(needs_bars).starts_with('|'),
+                "{:?}",
+                needs_bars
+            );
+        }
+    }
+
+    #[test]
+    fn resolve_text_ref_reads_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("docs")).unwrap();
+        std::fs::write(dir.path().join("docs/spec.md"), "one\ntwo\nthree\n").unwrap();
+
+        let whole = TextRef::use_path("docs/spec.md");
+        assert_eq!(
+            resolve_text_ref(&whole, dir.path()).unwrap(),
+            "one\ntwo\nthree\n"
+        );
+
+        let range = TextRef::UsePathRange {
+            path: "docs/./spec.md".to_string(),
+            start: 2,
+            end: 3,
+            alias: None,
+        };
+        assert_eq!(resolve_text_ref(&range, dir.path()).unwrap(), "two\nthree");
+    }
+
+    #[test]
+    fn resolve_text_ref_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = TextRef::use_path("nope.md");
+        let err = resolve_text_ref(&missing, dir.path()).unwrap_err();
+        assert!(err.to_string().contains("nope.md"));
+    }
+
+    #[test]
+    fn resolve_text_ref_rejects_traversal() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("base");
+        std::fs::create_dir(&base).unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+
+        let escape = TextRef::use_path("../secret.txt");
+        assert!(resolve_text_ref(&escape, &base).is_err());
+
+        let sneaky = TextRef::use_path("sub/../../secret.txt");
+        assert!(resolve_text_ref(&sneaky, &base).is_err());
+
+        let absolute = TextRef::use_path(dir.path().join("secret.txt").display().to_string());
+        assert!(resolve_text_ref(&absolute, &base).is_err());
+    }
+
+    #[test]
+    fn quote_str_exact_round_trips() {
+        for s in [
+            "a\nb",
+            "tab\there",
+            "say \"hi\"",
+            "back\\slash",
+            "all\n\t\"\\ mixed\r\n",
+            "",
+        ] {
+            let parsed = parse_value(&quote_str_exact(s)).unwrap();
+            assert_eq!(parsed.as_str(), Some(s), "round trip of {:?}", s);
+        }
+        assert_eq!(quote_str_exact("a\nb"), "\"a\nb\"");
+    }
+
     #[test]
     fn quote_str_escapes() {
         assert_eq!(quote_str("a\"b"), "\"a\\\"b\"");
         assert_eq!(quote_str("a\\b"), "\"a\\\\b\"");
         assert_eq!(quote_str("a\nb"), "\"a\\nb\"");
+        assert_eq!(quote_str("a\tb"), "\"a\\tb\"");
+        assert_eq!(quote_str("a\rb"), "\"a\\rb\"");
+        assert_eq!(quote_str("a\u{1}b"), "\"a\\x01;b\"");
     }
 }
@@ -3,17 +3,207 @@
 use crate::interactive::{
     default_history_path, run_line_loop, HistoryKind, LineLoopConfig, LoopControl,
 };
-use crate::log_viewer::command::Command;
-use anyhow::{Context, Result};
-use rusqlite::Connection;
+use crate::log_viewer::command::{Command, ExportFormat};
+use crate::persistence::sqlite::count_events_by_tool;
+use crate::persistence::ProgressSnapshot;
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{Connection, OptionalExtension};
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashSet};
+use std::ops::RangeInclusive;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+const TAIL_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Set by Ctrl-C outside of line editing; rustyline reads Ctrl-C as a key while prompting.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+/// Whether our Ctrl-C handler is installed, once `tail` has tried to install it.
+static INTERRUPT_HANDLER: Mutex<Option<bool>> = Mutex::new(None);
+
+/// Install the process-wide Ctrl-C handler used by `tail`, at most once.
+///
+/// Returns `Ok(false)` when the host program already installed its own
+/// handler, in which case `tail` cannot be stopped and should not start.
+fn install_interrupt_handler() -> Result<bool> {
+    let mut state = INTERRUPT_HANDLER
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some(installed) = *state {
+        return Ok(installed);
+    }
+
+    let installed = match ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::SeqCst)) {
+        Ok(()) => true,
+        Err(ctrlc::Error::MultipleHandlers) => false,
+        Err(e) => return Err(e).context("Failed to install Ctrl-C handler"),
+    };
+    *state = Some(installed);
+    Ok(installed)
+}
+
+/// Handler for a registered command, given the open database and the words
+/// after the command name.
+pub type CommandHandler = Box<dyn Fn(&Connection, &[String]) -> Result<String>>;
+
+struct RegisteredCommand {
+    usage: String,
+    args: RangeInclusive<usize>,
+    handler: CommandHandler,
+}
+
+/// The commands that parse to [`Command::WithArgs`], looked up by name.
+///
+/// [`CommandRegistry::new`] holds the built-in `show`, `events`, `export`,
+/// `stats` and `search` commands; adding a command means registering it
+/// here rather than touching [`Command`] or the dispatch in [`run`].
+pub struct CommandRegistry {
+    commands: BTreeMap<String, RegisteredCommand>,
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandRegistry {
+    /// A registry holding the built-in commands.
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry
+            .register(
+                "show",
+                "show <internal-id>\nshow all <offset> <limit>",
+                1..=3,
+                show_command,
+            )
+            .register("events", "events [tool-name]", 0..=1, |conn, args| {
+                render_events(conn, args.first().map(String::as_str))
+            })
+            .register("export", "export json <path>", 2..=2, export_command)
+            .register("stats", "stats", 0..=0, |conn, _| render_stats(conn))
+            .register("search", "search <term>", 1..=usize::MAX, |conn, args| {
+                render_search(conn, &args.join(" "))
+            });
+        registry
+    }
+
+    /// A registry with no commands beyond the [`Command`] shorthands.
+    pub fn empty() -> Self {
+        Self {
+            commands: BTreeMap::new(),
+        }
+    }
+
+    /// Register `name` (matched case-insensitively), taking a number of
+    /// arguments in `args` and shown in help as `usage`.
+    ///
+    /// Registering a name again replaces the earlier command.
+    pub fn register(
+        &mut self,
+        name: &str,
+        usage: &str,
+        args: RangeInclusive<usize>,
+        handler: impl Fn(&Connection, &[String]) -> Result<String> + 'static,
+    ) -> &mut Self {
+        self.commands.insert(
+            name.to_ascii_lowercase(),
+            RegisteredCommand {
+                usage: usage.to_string(),
+                args,
+                handler: Box::new(handler),
+            },
+        );
+        self
+    }
+
+    /// Parse a line, turning unregistered names and wrong argument counts into
+    /// [`Command::Unknown`].
+    pub fn parse(&self, input: &str) -> Command {
+        match Command::parse(input) {
+            Command::WithArgs { name, args } => match self.commands.get(&name) {
+                Some(cmd) if cmd.args.contains(&args.len()) => Command::WithArgs { name, args },
+                _ => Command::Unknown(input.trim().to_string()),
+            },
+            cmd => cmd,
+        }
+    }
+
+    /// Run the registered command `name` and return its output.
+    pub fn dispatch(&self, conn: &Connection, name: &str, args: &[String]) -> Result<String> {
+        let cmd = self
+            .commands
+            .get(name)
+            .ok_or_else(|| anyhow!("Unknown command: {}", name))?;
+        if !cmd.args.contains(&args.len()) {
+            return Err(anyhow!("Usage: {}", cmd.usage));
+        }
+        (cmd.handler)(conn, args)
+    }
+
+    /// Help for the [`Command`] shorthands followed by each registered command.
+    pub fn help_text(&self) -> String {
+        let mut out = Command::help_text().to_string();
+        for cmd in self.commands.values() {
+            out.push_str(&cmd.usage);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn show_command(conn: &Connection, args: &[String]) -> Result<String> {
+    match args {
+        [internal_id] => render_show(conn, internal_id),
+        [all, offset, limit] if all.eq_ignore_ascii_case("all") => {
+            let offset = offset
+                .parse()
+                .with_context(|| format!("Invalid offset: {}", offset))?;
+            let limit = limit
+                .parse()
+                .with_context(|| format!("Invalid limit: {}", limit))?;
+            render_show_page(conn, offset, limit)
+        }
+        _ => Err(anyhow!(
+            "Usage: show <internal-id> | show all <offset> <limit>"
+        )),
+    }
+}
+
+fn export_command(conn: &Connection, args: &[String]) -> Result<String> {
+    let format = match args[0].to_ascii_lowercase().as_str() {
+        "json" => ExportFormat::Json,
+        other => return Err(anyhow!("Unsupported export format: {}", other)),
+    };
+    let path = Path::new(&args[1]);
+    let count = export_snapshots(conn, format, path)?;
+    Ok(format!(
+        "Exported {} snapshots to {}\n",
+        count,
+        path.display()
+    ))
+}
 
 pub fn run(db_path: &Path) -> Result<()> {
+    run_with_registry(db_path, &CommandRegistry::new())
+}
+
+/// Like [`run`], with the commands in `registry` instead of the built-in set.
+pub fn run_with_registry(db_path: &Path, registry: &CommandRegistry) -> Result<()> {
     let conn = Connection::open(db_path)
         .with_context(|| format!("Failed to open sqlite db: {}", db_path.display()))?;
+    // Set by `clear`; the next line entered answers its confirmation prompt
+    let pending_clear: Cell<Option<bool>> = Cell::new(None);
 
     let cfg = LineLoopConfig::new(
-        || "log-viewer> ".to_string(),
+        || match pending_clear.get() {
+            Some(true) => "Delete all snapshots and tool call events? [y/N] ".to_string(),
+            Some(false) => "Delete all snapshots? [y/N] ".to_string(),
+            None => "log-viewer> ".to_string(),
+        },
         true,
         || LoopControl::Continue,
         || LoopControl::Break,
@@ -21,18 +211,39 @@ pub fn run(db_path: &Path) -> Result<()> {
     .with_history_file(default_history_path(HistoryKind::LogViewer));
 
     run_line_loop(cfg, |line| {
-        let cmd = Command::parse(line);
+        if let Some(events) = pending_clear.take() {
+            if matches!(line.trim().to_ascii_lowercase().as_str(), "y" | "yes") {
+                let count = clear(&conn, events)?;
+                println!("Deleted {} rows", count);
+            } else {
+                println!("Clear cancelled");
+            }
+            return Ok(LoopControl::Continue);
+        }
+
+        let cmd = registry.parse(line);
         match cmd {
             Command::Empty => {}
             Command::Help => {
-                println!("{}", Command::help_text());
+                println!("{}", registry.help_text());
             }
             Command::ShowAll => {
                 show_all(&conn)?;
             }
+            Command::Tail => {
+                tail(&conn)?;
+            }
+            Command::Clear { events } => {
+                pending_clear.set(Some(events));
+            }
+            // Registered commands report their errors and keep the viewer running
+            Command::WithArgs { name, args } => match registry.dispatch(&conn, &name, &args) {
+                Ok(out) => print!("{}", out),
+                Err(e) => println!("Error: {:#}", e),
+            },
             Command::Unknown(s) => {
                 println!("Unknown command: {}", s);
-                println!("{}", Command::help_text());
+                println!("{}", registry.help_text());
             }
         }
 
@@ -40,8 +251,11 @@ pub fn run(db_path: &Path) -> Result<()> {
     })
 }
 
+/// Rows printed by a bare `show all`.
+const SHOW_ALL_PAGE_SIZE: usize = 50;
+
 fn show_all(conn: &Connection) -> Result<()> {
-    let out = render_show_all(conn)?;
+    let out = render_show_page(conn, 0, SHOW_ALL_PAGE_SIZE)?;
     print!("{}", out);
     Ok(())
 }
@@ -71,12 +285,677 @@ pub fn render_show_all(conn: &Connection) -> Result<String> {
     for row in rows {
         let (internal_id, updated_at, event, snapshot_text) =
             row.context("Failed to read progress snapshot row")?;
-        out.push_str(&format!("== {} {} {} ==\n", internal_id, updated_at, event));
-        out.push_str(&snapshot_text);
-        if !snapshot_text.ends_with('\n') {
-            out.push('\n');
+        push_snapshot(&mut out, &internal_id, &updated_at, &event, &snapshot_text);
+    }
+
+    Ok(out)
+}
+
+/// Render `limit` snapshots starting at `offset`, newest first, with a footer
+/// pointing at the next page when more rows remain.
+pub fn render_show_page(conn: &Connection, offset: usize, limit: usize) -> Result<String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT internal_id, updated_at, event, snapshot_text \
+             FROM progress_snapshots \
+             ORDER BY updated_at DESC, internal_id \
+             LIMIT ?1 OFFSET ?2",
+        )
+        .context("Failed to prepare progress snapshot query")?;
+
+    // Fetch one extra row to learn whether another page follows
+    let fetch = i64::try_from(limit.saturating_add(1)).unwrap_or(i64::MAX);
+    let skip = i64::try_from(offset).unwrap_or(i64::MAX);
+    let rows = stmt
+        .query_map([fetch, skip], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })
+        .context("Failed to query progress snapshots")?;
+
+    let mut out = String::new();
+
+    for (shown, row) in rows.enumerate() {
+        let (internal_id, updated_at, event, snapshot_text) =
+            row.context("Failed to read progress snapshot row")?;
+        if shown == limit {
+            out.push_str(&format!(
+                "-- use `show all {} {}` for more --\n",
+                offset + limit,
+                limit
+            ));
+            break;
         }
+        push_snapshot(&mut out, &internal_id, &updated_at, &event, &snapshot_text);
     }
 
     Ok(out)
 }
+
+/// Print snapshots as they are inserted or updated, until Ctrl-C.
+fn tail(conn: &Connection) -> Result<()> {
+    if !install_interrupt_handler()? {
+        println!("Cannot follow snapshots: another Ctrl-C handler is already installed");
+        return Ok(());
+    }
+
+    let mut cursor = SnapshotCursor::default();
+    // Start from the current state: everything already stored counts as seen
+    render_snapshots_since(conn, &mut cursor)?;
+
+    println!("Following new snapshots (Ctrl-C to stop)");
+    INTERRUPTED.store(false, Ordering::SeqCst);
+    loop {
+        print!("{}", render_snapshots_since(conn, &mut cursor)?);
+
+        let deadline = Instant::now() + TAIL_POLL_INTERVAL;
+        while Instant::now() < deadline {
+            if INTERRUPTED.swap(false, Ordering::SeqCst) {
+                return Ok(());
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+/// How far `tail` has read `progress_snapshots`.
+///
+/// `updated_at` has whole-second resolution, so the cursor also remembers
+/// which snapshots it already printed for its latest second; rows landing
+/// later in that same second are still picked up.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotCursor {
+    updated_at: i64,
+    seen: HashSet<String>,
+}
+
+impl SnapshotCursor {
+    /// Cursor positioned after every snapshot updated before `updated_at`.
+    pub fn at(updated_at: i64) -> Self {
+        Self {
+            updated_at,
+            seen: HashSet::new(),
+        }
+    }
+
+    /// The latest `updated_at` seen so far.
+    pub fn updated_at(&self) -> i64 {
+        self.updated_at
+    }
+}
+
+/// Render snapshots not yet seen by `cursor`, oldest first, and advance it.
+pub fn render_snapshots_since(conn: &Connection, cursor: &mut SnapshotCursor) -> Result<String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT internal_id, updated_at, event, snapshot_text, CAST(updated_at AS INTEGER) \
+             FROM progress_snapshots \
+             WHERE CAST(updated_at AS INTEGER) >= ?1 \
+             ORDER BY CAST(updated_at AS INTEGER) ASC, internal_id ASC",
+        )
+        .context("Failed to prepare progress snapshot query")?;
+
+    let rows = stmt
+        .query_map([cursor.updated_at], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
+        })
+        .context("Failed to query progress snapshots")?;
+
+    let mut out = String::new();
+
+    for row in rows {
+        let (internal_id, updated_at, event, snapshot_text, seconds) =
+            row.context("Failed to read progress snapshot row")?;
+        if seconds > cursor.updated_at {
+            cursor.updated_at = seconds;
+            cursor.seen.clear();
+        } else if cursor.seen.contains(&internal_id) {
+            continue;
+        }
+        push_snapshot(&mut out, &internal_id, &updated_at, &event, &snapshot_text);
+        cursor.seen.insert(internal_id);
+    }
+
+    Ok(out)
+}
+
+/// Escape `%`, `_` and `\` so `term` matches literally in a `LIKE ... ESCAPE '\'` pattern.
+fn escape_like(term: &str) -> String {
+    let mut escaped = String::with_capacity(term.len());
+    for c in term.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+pub fn render_search(conn: &Connection, term: &str) -> Result<String> {
+    let pattern = format!("%{}%", escape_like(term));
+    let mut stmt = conn
+        .prepare(
+            "SELECT internal_id, updated_at, event, snapshot_text \
+             FROM progress_snapshots \
+             WHERE snapshot_text LIKE ?1 ESCAPE '\\' OR event LIKE ?1 ESCAPE '\\' \
+             ORDER BY updated_at DESC",
+        )
+        .context("Failed to prepare progress snapshot search")?;
+
+    let rows = stmt
+        .query_map([pattern], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })
+        .with_context(|| format!("Failed to search progress snapshots: {}", term))?;
+
+    let mut out = String::new();
+
+    for row in rows {
+        let (internal_id, updated_at, event, snapshot_text) =
+            row.context("Failed to read progress snapshot row")?;
+        push_snapshot(&mut out, &internal_id, &updated_at, &event, &snapshot_text);
+    }
+
+    Ok(out)
+}
+
+pub fn render_show(conn: &Connection, internal_id: &str) -> Result<String> {
+    let row = conn
+        .query_row(
+            "SELECT updated_at, event, snapshot_text \
+             FROM progress_snapshots \
+             WHERE internal_id = ?1",
+            [internal_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            },
+        )
+        .optional()
+        .with_context(|| format!("Failed to query progress snapshot: {}", internal_id))?;
+
+    let mut out = String::new();
+    match row {
+        Some((updated_at, event, snapshot_text)) => {
+            push_snapshot(&mut out, internal_id, &updated_at, &event, &snapshot_text);
+        }
+        None => out.push_str(&format!("No snapshot found for: {}\n", internal_id)),
+    }
+
+    Ok(out)
+}
+
+pub fn render_events(conn: &Connection, tool: Option<&str>) -> Result<String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT created_at, transport, tool_name, is_error \
+             FROM tool_call_events \
+             WHERE ?1 IS NULL OR tool_name = ?1 \
+             ORDER BY CAST(created_at AS INTEGER) DESC, id DESC",
+        )
+        .context("Failed to prepare tool call event query")?;
+
+    let rows = stmt
+        .query_map([tool], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, bool>(3)?,
+            ))
+        })
+        .context("Failed to query tool call events")?;
+
+    let mut out = String::new();
+
+    for row in rows {
+        let (created_at, transport, tool_name, is_error) =
+            row.context("Failed to read tool call event row")?;
+        let status = if is_error { "error" } else { "ok" };
+        out.push_str(&format!(
+            "{} {} {} {}\n",
+            created_at, transport, tool_name, status
+        ));
+    }
+
+    Ok(out)
+}
+
+pub fn render_stats(conn: &Connection) -> Result<String> {
+    let (calls, errors, tools) = conn
+        .query_row(
+            "SELECT COUNT(*), COALESCE(SUM(is_error), 0), COUNT(DISTINCT tool_name) \
+             FROM tool_call_events",
+            [],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            },
+        )
+        .context("Failed to query tool call event stats")?;
+
+    let snapshots = conn
+        .query_row("SELECT COUNT(*) FROM progress_snapshots", [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .context("Failed to count progress snapshots")?;
+
+    let mut out = String::new();
+    for (label, value) in [
+        ("tool calls", calls),
+        ("errors", errors),
+        ("distinct tools", tools),
+        ("snapshots", snapshots),
+    ] {
+        out.push_str(&format!("{:<16}{}\n", label, value));
+    }
+
+    let by_tool = count_events_by_tool(conn)?;
+    if !by_tool.is_empty() {
+        out.push_str("by tool\n");
+        for (tool, count) in by_tool {
+            out.push_str(&format!("  {:<14}{}\n", tool, count));
+        }
+    }
+
+    Ok(out)
+}
+
+/// Delete every progress snapshot, and every tool call event when `events`
+/// is set, returning the number of rows deleted.
+///
+/// Does not ask for confirmation; the interactive `clear` command does that.
+pub fn clear(conn: &Connection, events: bool) -> Result<usize> {
+    let tx = conn
+        .unchecked_transaction()
+        .context("Failed to begin clear transaction")?;
+    let mut deleted = tx
+        .execute("DELETE FROM progress_snapshots", [])
+        .context("Failed to delete progress snapshots")?;
+    if events {
+        deleted += tx
+            .execute("DELETE FROM tool_call_events", [])
+            .context("Failed to delete tool call events")?;
+    }
+    tx.commit().context("Failed to commit clear transaction")?;
+
+    Ok(deleted)
+}
+
+pub fn export_snapshots(conn: &Connection, format: ExportFormat, path: &Path) -> Result<usize> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT CAST(updated_at AS INTEGER), internal_id, event, snapshot_text \
+             FROM progress_snapshots \
+             ORDER BY updated_at DESC",
+        )
+        .context("Failed to prepare progress snapshot query")?;
+
+    let snapshots = stmt
+        .query_map([], |row| {
+            Ok(ProgressSnapshot {
+                updated_at: row.get(0)?,
+                internal_id: row.get(1)?,
+                event: row.get(2)?,
+                snapshot_text: row.get(3)?,
+            })
+        })
+        .context("Failed to query progress snapshots")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read progress snapshot row")?;
+
+    let contents = match format {
+        ExportFormat::Json => {
+            serde_json::to_string_pretty(&snapshots).context("Failed to serialize snapshots")?
+        }
+    };
+    std::fs::write(path, contents)
+        .with_context(|| format!("Failed to write export file: {}", path.display()))?;
+
+    Ok(snapshots.len())
+}
+
+fn push_snapshot(
+    out: &mut String,
+    internal_id: &str,
+    updated_at: &str,
+    event: &str,
+    snapshot_text: &str,
+) {
+    out.push_str(&format!("== {} {} {} ==\n", internal_id, updated_at, event));
+    out.push_str(snapshot_text);
+    if !snapshot_text.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../persistence/schema.sql"))
+            .unwrap();
+        conn.execute(
+            "INSERT INTO progress_snapshots (internal_id, updated_at, event, snapshot_text) \
+             VALUES ('abc123', '100', 'started', '(progress :step 1)')",
+            [],
+        )
+        .unwrap();
+        conn.execute_batch(
+            "INSERT INTO tool_call_events (created_at, transport, tool_name, canonical_tool_name, \
+               request_sexpr, response_sexpr, is_error) \
+             VALUES ('101', 'stdio', 'echo', 'echo', '(echo)', '(success)', 0); \
+             INSERT INTO tool_call_events (created_at, transport, tool_name, canonical_tool_name, \
+               request_sexpr, response_sexpr, is_error) \
+             VALUES ('102', 'http', 'build', 'build', '(build)', '(error)', 1);",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_render_show() {
+        let conn = seeded_db();
+
+        let out = render_show(&conn, "abc123").unwrap();
+        assert_eq!(out, "== abc123 100 started ==\n(progress :step 1)\n");
+
+        let out = render_show(&conn, "missing").unwrap();
+        assert_eq!(out, "No snapshot found for: missing\n");
+    }
+
+    #[test]
+    fn test_render_events() {
+        let conn = seeded_db();
+
+        let out = render_events(&conn, None).unwrap();
+        assert_eq!(out, "102 http build error\n101 stdio echo ok\n");
+
+        let out = render_events(&conn, Some("echo")).unwrap();
+        assert_eq!(out, "101 stdio echo ok\n");
+
+        // Timestamps sort numerically, not as text
+        conn.execute(
+            "INSERT INTO tool_call_events (created_at, transport, tool_name, canonical_tool_name, \
+               request_sexpr, response_sexpr, is_error) \
+             VALUES ('99', 'stdio', 'echo', 'echo', '(echo)', '(success)', 0)",
+            [],
+        )
+        .unwrap();
+        let out = render_events(&conn, Some("echo")).unwrap();
+        assert_eq!(out, "101 stdio echo ok\n99 stdio echo ok\n");
+    }
+
+    #[test]
+    fn test_export_snapshots_json() {
+        let conn = seeded_db();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snapshots.json");
+
+        let count = export_snapshots(&conn, ExportFormat::Json, &path).unwrap();
+        assert_eq!(count, 1);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let records = json.as_array().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["internal_id"], "abc123");
+        assert_eq!(records[0]["updated_at"], 100);
+    }
+
+    #[test]
+    fn test_export_snapshots_write_error() {
+        let conn = seeded_db();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing").join("snapshots.json");
+
+        let err = export_snapshots(&conn, ExportFormat::Json, &path).unwrap_err();
+        assert!(err.to_string().contains("snapshots.json"));
+    }
+
+    #[test]
+    fn test_render_snapshots_since() {
+        let conn = seeded_db();
+        conn.execute(
+            "INSERT INTO progress_snapshots (internal_id, updated_at, event, snapshot_text) \
+             VALUES ('def456', '105', 'finished', '(progress :step 2)')",
+            [],
+        )
+        .unwrap();
+
+        let mut cursor = SnapshotCursor::at(101);
+        let out = render_snapshots_since(&conn, &mut cursor).unwrap();
+        assert_eq!(out, "== def456 105 finished ==\n(progress :step 2)\n");
+        assert_eq!(cursor.updated_at(), 105);
+
+        let mut cursor = SnapshotCursor::default();
+        let out = render_snapshots_since(&conn, &mut cursor).unwrap();
+        assert!(out.starts_with("== abc123"));
+        assert_eq!(cursor.updated_at(), 105);
+
+        let out = render_snapshots_since(&conn, &mut cursor).unwrap();
+        assert!(out.is_empty());
+        assert_eq!(cursor.updated_at(), 105);
+    }
+
+    #[test]
+    fn test_render_snapshots_since_same_second() {
+        let conn = seeded_db();
+        let mut cursor = SnapshotCursor::default();
+        render_snapshots_since(&conn, &mut cursor).unwrap();
+        let latest = cursor.updated_at().to_string();
+
+        conn.execute(
+            "INSERT INTO progress_snapshots (internal_id, updated_at, event, snapshot_text) \
+             VALUES ('def456', ?1, 'started', '(progress :step 1)')",
+            [&latest],
+        )
+        .unwrap();
+        let out = render_snapshots_since(&conn, &mut cursor).unwrap();
+        assert!(out.starts_with("== def456"));
+
+        conn.execute(
+            "INSERT INTO progress_snapshots (internal_id, updated_at, event, snapshot_text) \
+             VALUES ('ghi789', ?1, 'started', '(progress :step 1)')",
+            [&latest],
+        )
+        .unwrap();
+        let out = render_snapshots_since(&conn, &mut cursor).unwrap();
+        assert!(out.starts_with("== ghi789"));
+        assert!(!out.contains("def456"));
+
+        let out = render_snapshots_since(&conn, &mut cursor).unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_render_stats() {
+        let conn = seeded_db();
+        conn.execute(
+            "INSERT INTO tool_call_events (created_at, transport, tool_name, canonical_tool_name, \
+               request_sexpr, response_sexpr, is_error) \
+             VALUES ('103', 'stdio', 'echo', 'echo', '(echo)', '(success)', 0)",
+            [],
+        )
+        .unwrap();
+
+        let out = render_stats(&conn).unwrap();
+        assert_eq!(
+            out,
+            "tool calls      3\n\
+             errors          1\n\
+             distinct tools  2\n\
+             snapshots       1\n\
+             by tool\n\
+             \x20 echo          2\n\
+             \x20 build         1\n"
+        );
+    }
+
+    #[test]
+    fn test_clear() {
+        let conn = seeded_db();
+        assert_eq!(clear(&conn, false).unwrap(), 1);
+        assert_eq!(render_events(&conn, None).unwrap().lines().count(), 2);
+
+        let conn = seeded_db();
+        assert_eq!(clear(&conn, true).unwrap(), 3);
+        assert!(render_events(&conn, None).unwrap().is_empty());
+        assert!(render_show_all(&conn).unwrap().is_empty());
+        assert_eq!(clear(&conn, true).unwrap(), 0);
+    }
+
+    fn with_args(name: &str, args: &[&str]) -> Command {
+        Command::WithArgs {
+            name: name.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_registry_parses_builtins() {
+        let registry = CommandRegistry::new();
+        assert_eq!(registry.parse("show all"), Command::ShowAll);
+        assert_eq!(registry.parse("stats"), with_args("stats", &[]));
+        assert_eq!(registry.parse("SHOW AbC"), with_args("show", &["AbC"]));
+        assert_eq!(
+            registry.parse("events git-commit"),
+            with_args("events", &["git-commit"])
+        );
+        assert_eq!(
+            registry.parse(r#"search "hello world""#),
+            with_args("search", &["hello world"])
+        );
+
+        // Wrong argument counts and unregistered names are unknown
+        for input in ["show", "stats now", "search", "export json", "nope"] {
+            assert_eq!(registry.parse(input), Command::Unknown(input.to_string()));
+        }
+        assert_eq!(
+            CommandRegistry::empty().parse("stats"),
+            Command::Unknown("stats".to_string())
+        );
+    }
+
+    #[test]
+    fn test_registry_dispatches_builtins() {
+        let conn = seeded_db();
+        let registry = CommandRegistry::new();
+        let run = |line: &str| match registry.parse(line) {
+            Command::WithArgs { name, args } => registry.dispatch(&conn, &name, &args),
+            other => panic!("unexpected {:?}", other),
+        };
+
+        assert_eq!(
+            run("show abc123").unwrap(),
+            "== abc123 100 started ==\n(progress :step 1)\n"
+        );
+        assert_eq!(
+            run("show all 0 5").unwrap(),
+            render_show_page(&conn, 0, 5).unwrap()
+        );
+        assert!(run("show all x 5").is_err());
+        assert_eq!(run("events echo").unwrap(), "101 stdio echo ok\n");
+        assert_eq!(run("stats").unwrap(), render_stats(&conn).unwrap());
+        assert!(run("search step").unwrap().contains("== abc123 "));
+        assert!(run("export csv out.csv").is_err());
+    }
+
+    #[test]
+    fn test_registry_parse_and_dispatch() {
+        let conn = seeded_db();
+        let mut registry = CommandRegistry::new();
+        registry.register("count", "count <table>", 1..=1, |conn, args| {
+            let table = match args[0].as_str() {
+                "events" => "tool_call_events",
+                "snapshots" => "progress_snapshots",
+                _ => anyhow::bail!("usage: count <events|snapshots>"),
+            };
+            let n: i64 = conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                row.get(0)
+            })?;
+            Ok(format!("{}\n", n))
+        });
+
+        let args = vec!["events".to_string()];
+        assert_eq!(
+            registry.parse("Count events"),
+            with_args("count", &["events"])
+        );
+        assert_eq!(registry.dispatch(&conn, "count", &args).unwrap(), "2\n");
+        assert!(registry.dispatch(&conn, "count", &[]).is_err());
+        assert!(registry.dispatch(&conn, "missing", &[]).is_err());
+        assert!(registry.help_text().contains("\ncount <table>\n"));
+    }
+
+    #[test]
+    fn test_render_search() {
+        let conn = seeded_db();
+        conn.execute_batch(
+            "INSERT INTO progress_snapshots (internal_id, updated_at, event, snapshot_text) \
+             VALUES ('pct', '101', 'progress', '(progress :done \"50%\")'); \
+             INSERT INTO progress_snapshots (internal_id, updated_at, event, snapshot_text) \
+             VALUES ('plain', '102', 'timeout', '(progress :done \"500\")');",
+        )
+        .unwrap();
+
+        let out = render_search(&conn, "step").unwrap();
+        assert!(out.contains("== abc123 "));
+        assert!(!out.contains("pct") && !out.contains("plain"));
+
+        // Matches the event column too
+        let out = render_search(&conn, "timeout").unwrap();
+        assert!(out.starts_with("== plain "));
+
+        // `%` and `_` are literal, not wildcards
+        let out = render_search(&conn, "50%").unwrap();
+        assert!(out.contains("== pct ") && !out.contains("== plain "));
+        assert!(render_search(&conn, "st_p").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_render_show_page() {
+        let conn = seeded_db();
+        conn.execute_batch(
+            "INSERT INTO progress_snapshots (internal_id, updated_at, event, snapshot_text) \
+             VALUES ('second', '200', 'running', '(progress :step 2)'); \
+             INSERT INTO progress_snapshots (internal_id, updated_at, event, snapshot_text) \
+             VALUES ('third', '300', 'running', '(progress :step 3)');",
+        )
+        .unwrap();
+
+        // Newest first: third, second, abc123
+        let out = render_show_page(&conn, 1, 1).unwrap();
+        assert_eq!(
+            out,
+            "== second 200 running ==\n(progress :step 2)\n\
+             -- use `show all 2 1` for more --\n"
+        );
+
+        let out = render_show_page(&conn, 2, 5).unwrap();
+        assert_eq!(out, "== abc123 100 started ==\n(progress :step 1)\n");
+    }
+}
@@ -3,10 +3,26 @@
 use anyhow::Result;
 use std::str::FromStr;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+}
+
+/// A parsed log viewer line.
+///
+/// Only commands that need the line loop itself (a confirmation prompt, or
+/// following new rows until Ctrl-C) or are handy shorthands get their own
+/// variant. Everything else parses to [`Command::WithArgs`], with the name
+/// lowercased and the arguments as typed, and is looked up in a
+/// `CommandRegistry`, which holds the built-in `show`, `events`, `export`,
+/// `stats` and `search` commands.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Command {
     Help,
     ShowAll,
+    Tail,
+    Clear { events: bool },
+    WithArgs { name: String, args: Vec<String> },
     Unknown(String),
     Empty,
 }
@@ -21,7 +37,9 @@ impl Command {
     pub fn help_text() -> &'static str {
         "Commands:\n\
 help\n\
-show all\n"
+show all\n\
+tail\n\
+clear [events]\n"
     }
 }
 
@@ -34,15 +52,149 @@ impl FromStr for Command {
             return Ok(Command::Empty);
         }
 
-        let normalized = trimmed
-            .split_whitespace()
+        let Some(words) = tokenize(trimmed) else {
+            return Ok(Command::Unknown(trimmed.to_string()));
+        };
+        let normalized = words
+            .iter()
             .map(|w| w.to_ascii_lowercase())
             .collect::<Vec<_>>();
 
         match normalized.as_slice() {
             [cmd] if cmd == "help" => Ok(Command::Help),
             [a, b] if a == "show" && b == "all" => Ok(Command::ShowAll),
-            _ => Ok(Command::Unknown(trimmed.to_string())),
+            [cmd] if cmd == "tail" => Ok(Command::Tail),
+            [cmd] if cmd == "clear" => Ok(Command::Clear { events: false }),
+            [cmd, what] if cmd == "clear" && what == "events" => {
+                Ok(Command::Clear { events: true })
+            }
+            // Arguments such as ids are case-sensitive, so take them from the original input
+            [cmd, ..] => Ok(Command::WithArgs {
+                name: cmd.clone(),
+                args: words[1..].to_vec(),
+            }),
+            [] => Ok(Command::Unknown(trimmed.to_string())),
+        }
+    }
+}
+
+/// Split a command line on whitespace, keeping double-quoted text as one word.
+///
+/// Inside quotes, `\"` and `\\` stand for a literal quote and backslash.
+/// Returns `None` for an unterminated quote.
+fn tokenize(input: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut in_quotes = false;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                in_word = true;
+            }
+            '\\' if in_quotes => match chars.next() {
+                Some(next @ ('"' | '\\')) => current.push(next),
+                Some(next) => {
+                    current.push('\\');
+                    current.push(next);
+                }
+                None => return None,
+            },
+            c if c.is_whitespace() && !in_quotes => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_word = true;
+            }
         }
     }
+
+    if in_quotes {
+        return None;
+    }
+    if in_word {
+        words.push(current);
+    }
+    Some(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_args(name: &str, args: &[&str]) -> Command {
+        Command::WithArgs {
+            name: name.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_conveniences() {
+        assert_eq!(Command::parse("help"), Command::Help);
+        assert_eq!(Command::parse("SHOW ALL"), Command::ShowAll);
+        assert_eq!(Command::parse("Tail"), Command::Tail);
+        assert_eq!(Command::parse("  "), Command::Empty);
+    }
+
+    #[test]
+    fn test_parse_clear() {
+        assert_eq!(Command::parse("clear"), Command::Clear { events: false });
+        assert_eq!(
+            Command::parse("Clear Events"),
+            Command::Clear { events: true }
+        );
+        assert_eq!(
+            Command::parse("clear snapshots"),
+            with_args("clear", &["snapshots"])
+        );
+    }
+
+    #[test]
+    fn test_parse_with_args() {
+        // Names are lowercased, arguments keep their case
+        assert_eq!(Command::parse("SHOW AbC"), with_args("show", &["AbC"]));
+        assert_eq!(Command::parse("stats"), with_args("stats", &[]));
+        assert_eq!(
+            Command::parse("show all 50 25"),
+            with_args("show", &["all", "50", "25"])
+        );
+        assert_eq!(
+            Command::parse("export JSON out/Snapshots.json"),
+            with_args("export", &["JSON", "out/Snapshots.json"])
+        );
+    }
+
+    #[test]
+    fn test_tokenize_quotes() {
+        assert_eq!(
+            tokenize(r#"search "hello world""#).unwrap(),
+            vec!["search", "hello world"]
+        );
+        assert_eq!(
+            tokenize(r#"search "say \"hi\"" x"#).unwrap(),
+            vec!["search", r#"say "hi""#, "x"]
+        );
+        assert_eq!(tokenize(r#"search """#).unwrap(), vec!["search", ""]);
+        assert!(tokenize(r#"search "unterminated"#).is_none());
+    }
+
+    #[test]
+    fn test_parse_quoted_arguments() {
+        assert_eq!(
+            Command::parse(r#"search "hello world""#),
+            with_args("search", &["hello world"])
+        );
+        assert_eq!(
+            Command::parse(r#"search "oops"#),
+            Command::Unknown(r#"search "oops"#.to_string())
+        );
+    }
 }
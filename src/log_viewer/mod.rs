@@ -3,7 +3,8 @@
 pub mod cli;
 pub mod command;
 
-pub use command::Command;
+pub use cli::{CommandHandler, CommandRegistry};
+pub use command::{Command, ExportFormat};
 
 use anyhow::Result;
 use std::path::{Path, PathBuf};
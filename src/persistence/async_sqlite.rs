@@ -0,0 +1,120 @@
+#![allow(missing_docs)]
+
+use crate::persistence::sqlite::{ProgressSnapshot, SqlitePersistence, ToolCallEvent};
+use anyhow::{Context, Result};
+
+/// Async front end for [`SqlitePersistence`] that runs each call on tokio's
+/// blocking thread pool, so the runtime's worker threads never wait on SQLite.
+///
+/// Calls still serialize on the wrapped connection's mutex.
+#[derive(Clone)]
+pub struct AsyncSqlitePersistence {
+    inner: SqlitePersistence,
+}
+
+impl AsyncSqlitePersistence {
+    pub fn new(inner: SqlitePersistence) -> Self {
+        Self { inner }
+    }
+
+    /// The wrapped synchronous persistence.
+    pub fn blocking(&self) -> &SqlitePersistence {
+        &self.inner
+    }
+
+    async fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&SqlitePersistence) -> Result<T> + Send + 'static,
+    {
+        let inner = self.inner.clone();
+        tokio::task::spawn_blocking(move || f(&inner))
+            .await
+            .context("Persistence task failed")?
+    }
+
+    pub async fn insert_tool_call_event(&self, event: &ToolCallEvent) -> Result<()> {
+        let event = event.clone();
+        self.run(move |db| db.insert_tool_call_event(&event)).await
+    }
+
+    pub async fn insert_tool_call_events(&self, events: &[ToolCallEvent]) -> Result<()> {
+        let events = events.to_vec();
+        self.run(move |db| db.insert_tool_call_events(&events))
+            .await
+    }
+
+    pub async fn upsert_progress_snapshot(&self, snapshot: &ProgressSnapshot) -> Result<()> {
+        let snapshot = snapshot.clone();
+        self.run(move |db| db.upsert_progress_snapshot(&snapshot))
+            .await
+    }
+
+    pub async fn list_tool_call_events(&self, limit: Option<usize>) -> Result<Vec<ToolCallEvent>> {
+        self.run(move |db| db.list_tool_call_events(limit)).await
+    }
+
+    pub async fn events_between(&self, start: i64, end: i64) -> Result<Vec<ToolCallEvent>> {
+        self.run(move |db| db.events_between(start, end)).await
+    }
+
+    pub async fn counts_by_tool(&self) -> Result<Vec<(String, i64)>> {
+        self.run(|db| db.counts_by_tool()).await
+    }
+
+    pub async fn distinct_clients(&self) -> Result<Vec<String>> {
+        self.run(|db| db.distinct_clients()).await
+    }
+
+    pub async fn events_for_client(&self, client: &str) -> Result<Vec<ToolCallEvent>> {
+        let client = client.to_string();
+        self.run(move |db| db.events_for_client(&client)).await
+    }
+
+    pub async fn get_progress_snapshot(
+        &self,
+        internal_id: &str,
+    ) -> Result<Option<ProgressSnapshot>> {
+        let internal_id = internal_id.to_string();
+        self.run(move |db| db.get_progress_snapshot(&internal_id))
+            .await
+    }
+
+    pub async fn prune_older_than(&self, seconds: u64) -> Result<usize> {
+        self.run(move |db| db.prune_older_than(seconds)).await
+    }
+}
+
+impl From<SqlitePersistence> for AsyncSqlitePersistence {
+    fn from(inner: SqlitePersistence) -> Self {
+        Self::new(inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_async_insert_and_read_back() {
+        let db = AsyncSqlitePersistence::new(SqlitePersistence::open_in_memory().unwrap());
+        let event = ToolCallEvent {
+            created_at: 0,
+            transport: "stdio".to_string(),
+            client_name: None,
+            tool_name: "echo".to_string(),
+            canonical_tool_name: "echo".to_string(),
+            request_sexpr: "(echo)".to_string(),
+            response_sexpr: "(success)".to_string(),
+            is_error: false,
+            internal_id: Some("abc".to_string()),
+        };
+
+        db.insert_tool_call_event(&event).await.unwrap();
+
+        let events = db.list_tool_call_events(None).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].tool_name, "echo");
+        assert_eq!(events[0].internal_id.as_deref(), Some("abc"));
+    }
+}
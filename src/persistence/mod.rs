@@ -1,3 +1,9 @@
+#[cfg(feature = "persistence-async")]
+pub mod async_sqlite;
+pub mod pooled;
 pub mod sqlite;
 
-pub use sqlite::{ProgressSnapshot, SqlitePersistence, ToolCallEvent};
+#[cfg(feature = "persistence-async")]
+pub use async_sqlite::AsyncSqlitePersistence;
+pub use pooled::PooledPersistence;
+pub use sqlite::{PersistenceOptions, ProgressSnapshot, SqlitePersistence, ToolCallEvent};
@@ -0,0 +1,226 @@
+#![allow(missing_docs)]
+
+use crate::persistence::sqlite::{
+    configure, count_events_by_tool, get_snapshot, insert_event, insert_events, list_clients,
+    list_events, list_events_between, list_events_for_client, lock_conn, migrate, prune,
+    unix_epoch_seconds_string, upsert_snapshot, PersistenceOptions, ProgressSnapshot,
+    ToolCallEvent,
+};
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OpenFlags};
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+
+/// SQLite persistence with one write connection and a pool of read connections.
+///
+/// Offers the same insert/query methods as [`SqlitePersistence`](super::SqlitePersistence).
+/// With WAL enabled (the default), reads run concurrently with each other and
+/// with the writer, while writes are serialized on the single write connection.
+#[derive(Clone)]
+pub struct PooledPersistence {
+    inner: Arc<Pool>,
+}
+
+struct Pool {
+    writer: Mutex<Connection>,
+    readers: Mutex<Vec<Connection>>,
+    reader_returned: Condvar,
+}
+
+/// A read connection checked out of the pool; returned when dropped.
+struct Reader<'a> {
+    pool: &'a Pool,
+    conn: Option<Connection>,
+}
+
+impl Deref for Reader<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn
+            .as_ref()
+            .expect("reader connection already returned")
+    }
+}
+
+impl Drop for Reader<'_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            lock_readers(&self.pool.readers).push(conn);
+            self.pool.reader_returned.notify_one();
+        }
+    }
+}
+
+/// The reader list only holds idle connections, so a poisoned lock is still usable.
+fn lock_readers(readers: &Mutex<Vec<Connection>>) -> MutexGuard<'_, Vec<Connection>> {
+    readers.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl PooledPersistence {
+    /// Open `db_path` with `readers` read connections (at least one).
+    pub fn open(db_path: &Path, readers: usize) -> Result<Self> {
+        Self::open_with_options(db_path, readers, PersistenceOptions::default())
+    }
+
+    pub fn open_with_options(
+        db_path: &Path,
+        readers: usize,
+        options: PersistenceOptions,
+    ) -> Result<Self> {
+        let mut writer = Connection::open(db_path)
+            .with_context(|| format!("Failed to open sqlite db: {}", db_path.display()))?;
+        configure(&writer, &options)?;
+        migrate(&mut writer)?;
+
+        let read_flags = OpenFlags::SQLITE_OPEN_READ_ONLY
+            | OpenFlags::SQLITE_OPEN_URI
+            | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+        let reader_conns = (0..readers.max(1))
+            .map(|_| {
+                let conn = Connection::open_with_flags(db_path, read_flags).with_context(|| {
+                    format!("Failed to open sqlite reader: {}", db_path.display())
+                })?;
+                conn.busy_timeout(options.busy_timeout)
+                    .context("Failed to set sqlite busy timeout")?;
+                Ok(conn)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            inner: Arc::new(Pool {
+                writer: Mutex::new(writer),
+                readers: Mutex::new(reader_conns),
+                reader_returned: Condvar::new(),
+            }),
+        })
+    }
+
+    /// Check out a read connection, waiting for one to be returned if all are in use.
+    fn reader(&self) -> Reader<'_> {
+        let mut readers = lock_readers(&self.inner.readers);
+        loop {
+            if let Some(conn) = readers.pop() {
+                return Reader {
+                    pool: &self.inner,
+                    conn: Some(conn),
+                };
+            }
+            readers = self
+                .inner
+                .reader_returned
+                .wait(readers)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    pub fn insert_tool_call_event(&self, event: &ToolCallEvent) -> Result<()> {
+        let now = unix_epoch_seconds_string()?;
+        insert_event(&*lock_conn(&self.inner.writer)?, &now, event)
+    }
+
+    /// Insert several events in one transaction; if any insert fails, none are kept.
+    pub fn insert_tool_call_events(&self, events: &[ToolCallEvent]) -> Result<()> {
+        insert_events(&mut *lock_conn(&self.inner.writer)?, events)
+    }
+
+    pub fn upsert_progress_snapshot(&self, snapshot: &ProgressSnapshot) -> Result<()> {
+        upsert_snapshot(&*lock_conn(&self.inner.writer)?, snapshot)
+    }
+
+    /// Delete events older than `seconds` ago, plus snapshots that are that old and
+    /// no longer referenced by any event. Returns the number of rows removed.
+    pub fn prune_older_than(&self, seconds: u64) -> Result<usize> {
+        prune(&mut *lock_conn(&self.inner.writer)?, seconds)
+    }
+
+    /// Most recent tool call events first, at most `limit` of them.
+    pub fn list_tool_call_events(&self, limit: Option<usize>) -> Result<Vec<ToolCallEvent>> {
+        list_events(&self.reader(), limit)
+    }
+
+    /// Events with `start <= created_at <= end` (Unix seconds), oldest first.
+    pub fn events_between(&self, start: i64, end: i64) -> Result<Vec<ToolCallEvent>> {
+        list_events_between(&self.reader(), start, end)
+    }
+
+    /// Number of events per canonical tool name, most-called first.
+    pub fn counts_by_tool(&self) -> Result<Vec<(String, i64)>> {
+        count_events_by_tool(&self.reader())
+    }
+
+    /// Client names that have recorded events, sorted; events without a client are skipped.
+    pub fn distinct_clients(&self) -> Result<Vec<String>> {
+        list_clients(&self.reader())
+    }
+
+    /// Events recorded by `client`, most recent first.
+    pub fn events_for_client(&self, client: &str) -> Result<Vec<ToolCallEvent>> {
+        list_events_for_client(&self.reader(), client)
+    }
+
+    pub fn get_progress_snapshot(&self, internal_id: &str) -> Result<Option<ProgressSnapshot>> {
+        get_snapshot(&self.reader(), internal_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use tempfile::tempdir;
+
+    fn event(tool_name: &str) -> ToolCallEvent {
+        ToolCallEvent {
+            created_at: 0,
+            transport: "stdio".to_string(),
+            client_name: None,
+            tool_name: tool_name.to_string(),
+            canonical_tool_name: tool_name.to_string(),
+            request_sexpr: format!("({})", tool_name),
+            response_sexpr: "(success)".to_string(),
+            is_error: false,
+            internal_id: None,
+        }
+    }
+
+    #[test]
+    fn test_concurrent_reads_while_writing() {
+        let dir = tempdir().unwrap();
+        let db = PooledPersistence::open(&dir.path().join("log.db"), 2).unwrap();
+        const WRITES: usize = 20;
+
+        let writer = {
+            let db = db.clone();
+            thread::spawn(move || {
+                for i in 0..WRITES {
+                    db.insert_tool_call_event(&event(&format!("tool-{}", i)))
+                        .unwrap();
+                }
+            })
+        };
+
+        // More reader threads than pooled connections, so some must wait
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let db = db.clone();
+                thread::spawn(move || {
+                    let mut last = 0;
+                    for _ in 0..50 {
+                        let count = db.list_tool_call_events(None).unwrap().len();
+                        assert!(count >= last && count <= WRITES);
+                        last = count;
+                    }
+                })
+            })
+            .collect();
+
+        writer.join().unwrap();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+
+        assert_eq!(db.list_tool_call_events(None).unwrap().len(), WRITES);
+    }
+}
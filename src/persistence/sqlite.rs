@@ -1,13 +1,15 @@
 #![allow(missing_docs)]
 
 use anyhow::{Context, Result};
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use std::path::Path;
-use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone)]
 pub struct ToolCallEvent {
+    /// Unix seconds when the event was recorded; 0 means "now" when inserting
+    pub created_at: i64,
     pub transport: String,
     pub client_name: Option<String>,
     pub tool_name: String,
@@ -19,12 +21,33 @@ pub struct ToolCallEvent {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProgressSnapshot {
+    /// Unix seconds of the last update; 0 means "now" when upserting
+    pub updated_at: i64,
     pub internal_id: String,
     pub event: String,
     pub snapshot_text: String,
 }
 
+/// Connection settings applied by [`SqlitePersistence::open_with_options`].
+#[derive(Debug, Clone)]
+pub struct PersistenceOptions {
+    /// Use write-ahead logging so readers (like the log viewer) don't block the writer
+    pub wal: bool,
+    /// How long to wait on a locked database before failing with `SQLITE_BUSY`
+    pub busy_timeout: Duration,
+}
+
+impl Default for PersistenceOptions {
+    fn default() -> Self {
+        Self {
+            wal: true,
+            busy_timeout: Duration::from_millis(5000),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct SqlitePersistence {
     conn: Arc<Mutex<Connection>>,
@@ -32,71 +55,699 @@ pub struct SqlitePersistence {
 
 impl SqlitePersistence {
     pub fn open(db_path: &Path) -> Result<Self> {
+        Self::open_with_options(db_path, PersistenceOptions::default())
+    }
+
+    pub fn open_with_options(db_path: &Path, options: PersistenceOptions) -> Result<Self> {
         let conn = Connection::open(db_path)
             .with_context(|| format!("Failed to open sqlite db: {}", db_path.display()))?;
+        Self::init(conn, options)
+    }
+
+    /// Open a private in-memory database with the full schema, mainly for tests.
+    pub fn open_in_memory() -> Result<Self> {
+        let conn = Connection::open_in_memory().context("Failed to open in-memory sqlite db")?;
+        let options = PersistenceOptions {
+            wal: false,
+            ..PersistenceOptions::default()
+        };
+        Self::init(conn, options)
+    }
 
-        let schema_sql = include_str!("schema.sql");
-        conn.execute_batch(schema_sql)
-            .context("Failed to initialize sqlite schema")?;
+    fn init(mut conn: Connection, options: PersistenceOptions) -> Result<Self> {
+        configure(&conn, &options)?;
+        migrate(&mut conn)?;
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
         })
     }
 
+    /// Lock the connection, failing instead of panicking if another thread
+    /// panicked while holding it (it may have left a transaction half done).
+    fn lock(&self) -> Result<MutexGuard<'_, Connection>> {
+        lock_conn(&self.conn)
+    }
+
     pub fn insert_tool_call_event(&self, event: &ToolCallEvent) -> Result<()> {
-        let created_at = unix_epoch_seconds_string()?;
-        let is_error = if event.is_error { 1 } else { 0 };
-
-        let conn = self.conn.lock().expect("sqlite connection mutex poisoned");
-        conn.execute(
-            "INSERT INTO tool_call_events (created_at, transport, client_name, tool_name, canonical_tool_name, request_sexpr, response_sexpr, is_error, internal_id)\
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-            params![
-                created_at,
-                event.transport,
-                event.client_name,
-                event.tool_name,
-                event.canonical_tool_name,
-                event.request_sexpr,
-                event.response_sexpr,
-                is_error,
-                event.internal_id,
-            ],
-        )
-        .context("Failed to insert tool call event")?;
+        let now = unix_epoch_seconds_string()?;
+
+        let conn = self.lock()?;
+        insert_event(&conn, &now, event)
+    }
 
-        Ok(())
+    /// Insert several events in one transaction; if any insert fails, none are kept.
+    pub fn insert_tool_call_events(&self, events: &[ToolCallEvent]) -> Result<()> {
+        let mut conn = self.lock()?;
+        insert_events(&mut conn, events)
     }
 
     pub fn upsert_progress_snapshot(&self, snapshot: &ProgressSnapshot) -> Result<()> {
-        let updated_at = unix_epoch_seconds_string()?;
-
-        let conn = self.conn.lock().expect("sqlite connection mutex poisoned");
-        conn.execute(
-            "INSERT INTO progress_snapshots (internal_id, updated_at, event, snapshot_text)
-             VALUES (?1, ?2, ?3, ?4)
-             ON CONFLICT(internal_id) DO UPDATE SET
-               updated_at = excluded.updated_at,
-               event = excluded.event,
-               snapshot_text = excluded.snapshot_text",
-            params![
-                snapshot.internal_id,
-                updated_at,
-                snapshot.event,
-                snapshot.snapshot_text,
-            ],
+        let conn = self.lock()?;
+        upsert_snapshot(&conn, snapshot)
+    }
+
+    /// Most recent tool call events first, at most `limit` of them.
+    pub fn list_tool_call_events(&self, limit: Option<usize>) -> Result<Vec<ToolCallEvent>> {
+        let conn = self.lock()?;
+        list_events(&conn, limit)
+    }
+
+    /// Delete events older than `seconds` ago, plus snapshots that are that old and
+    /// no longer referenced by any event. Returns the number of rows removed.
+    pub fn prune_older_than(&self, seconds: u64) -> Result<usize> {
+        let mut conn = self.lock()?;
+        prune(&mut conn, seconds)
+    }
+
+    /// Events with `start <= created_at <= end` (Unix seconds), oldest first.
+    pub fn events_between(&self, start: i64, end: i64) -> Result<Vec<ToolCallEvent>> {
+        let conn = self.lock()?;
+        list_events_between(&conn, start, end)
+    }
+
+    /// Number of events per canonical tool name, most-called first.
+    pub fn counts_by_tool(&self) -> Result<Vec<(String, i64)>> {
+        let conn = self.lock()?;
+        count_events_by_tool(&conn)
+    }
+
+    /// Client names that have recorded events, sorted; events without a client are skipped.
+    pub fn distinct_clients(&self) -> Result<Vec<String>> {
+        let conn = self.lock()?;
+        list_clients(&conn)
+    }
+
+    /// Events recorded by `client`, most recent first.
+    pub fn events_for_client(&self, client: &str) -> Result<Vec<ToolCallEvent>> {
+        let conn = self.lock()?;
+        list_events_for_client(&conn, client)
+    }
+
+    pub fn get_progress_snapshot(&self, internal_id: &str) -> Result<Option<ProgressSnapshot>> {
+        let conn = self.lock()?;
+        get_snapshot(&conn, internal_id)
+    }
+}
+
+// The statements below are shared with `PooledPersistence`.
+
+pub(super) fn lock_conn(conn: &Mutex<Connection>) -> Result<MutexGuard<'_, Connection>> {
+    conn.lock()
+        .map_err(|_| anyhow::anyhow!("Internal lock poisoned: sqlite connection"))
+}
+
+pub(super) fn configure(conn: &Connection, options: &PersistenceOptions) -> Result<()> {
+    conn.busy_timeout(options.busy_timeout)
+        .context("Failed to set sqlite busy timeout")?;
+    if options.wal {
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))
+            .context("Failed to enable sqlite WAL mode")?;
+    }
+    Ok(())
+}
+
+pub(super) fn insert_events(conn: &mut Connection, events: &[ToolCallEvent]) -> Result<()> {
+    let now = unix_epoch_seconds_string()?;
+
+    let tx = conn
+        .transaction()
+        .context("Failed to begin tool call event transaction")?;
+    for event in events {
+        insert_event(&tx, &now, event)?;
+    }
+    tx.commit()
+        .context("Failed to commit tool call event transaction")?;
+
+    Ok(())
+}
+
+pub(super) fn upsert_snapshot(conn: &Connection, snapshot: &ProgressSnapshot) -> Result<()> {
+    let updated_at = timestamp_or_now(snapshot.updated_at)?;
+
+    conn.execute(
+        "INSERT INTO progress_snapshots (internal_id, updated_at, event, snapshot_text, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?2)
+         ON CONFLICT(internal_id) DO UPDATE SET
+           updated_at = excluded.updated_at,
+           event = excluded.event,
+           snapshot_text = excluded.snapshot_text",
+        params![
+            snapshot.internal_id,
+            updated_at,
+            snapshot.event,
+            snapshot.snapshot_text,
+        ],
+    )
+    .context("Failed to upsert progress snapshot")?;
+
+    Ok(())
+}
+
+/// Column list shared by the event queries, in the order [`event_from_row`] reads it.
+const SELECT_EVENTS: &str = "SELECT CAST(created_at AS INTEGER), transport, client_name, tool_name, canonical_tool_name, request_sexpr, response_sexpr, is_error, internal_id \
+     FROM tool_call_events";
+
+fn event_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ToolCallEvent> {
+    Ok(ToolCallEvent {
+        created_at: row.get(0)?,
+        transport: row.get(1)?,
+        client_name: row.get(2)?,
+        tool_name: row.get(3)?,
+        canonical_tool_name: row.get(4)?,
+        request_sexpr: row.get(5)?,
+        response_sexpr: row.get(6)?,
+        is_error: row.get(7)?,
+        internal_id: row.get(8)?,
+    })
+}
+
+/// Run `SELECT_EVENTS` followed by `clauses` (WHERE/ORDER BY/LIMIT) with `params`.
+fn query_events(
+    conn: &Connection,
+    clauses: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<ToolCallEvent>> {
+    let mut stmt = conn
+        .prepare(&format!("{} {}", SELECT_EVENTS, clauses))
+        .context("Failed to prepare tool call event query")?;
+
+    let events = stmt
+        .query_map(params, event_from_row)
+        .context("Failed to query tool call events")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read tool call event row")?;
+
+    Ok(events)
+}
+
+pub(super) fn list_events(conn: &Connection, limit: Option<usize>) -> Result<Vec<ToolCallEvent>> {
+    // SQLite treats a negative LIMIT as no limit
+    let limit = limit.map_or(-1, |l| i64::try_from(l).unwrap_or(i64::MAX));
+
+    query_events(
+        conn,
+        "ORDER BY CAST(created_at AS INTEGER) DESC, id DESC LIMIT ?1",
+        [limit],
+    )
+}
+
+pub(super) fn list_events_between(
+    conn: &Connection,
+    start: i64,
+    end: i64,
+) -> Result<Vec<ToolCallEvent>> {
+    query_events(
+        conn,
+        "WHERE CAST(created_at AS INTEGER) BETWEEN ?1 AND ?2 \
+         ORDER BY CAST(created_at AS INTEGER) ASC, id ASC",
+        [start, end],
+    )
+}
+
+pub(super) fn list_events_for_client(
+    conn: &Connection,
+    client: &str,
+) -> Result<Vec<ToolCallEvent>> {
+    query_events(
+        conn,
+        "WHERE client_name = ?1 \
+         ORDER BY CAST(created_at AS INTEGER) DESC, id DESC",
+        [client],
+    )
+}
+
+pub(super) fn list_clients(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT client_name \
+             FROM tool_call_events \
+             WHERE client_name IS NOT NULL \
+             ORDER BY client_name",
+        )
+        .context("Failed to prepare client name query")?;
+
+    let clients = stmt
+        .query_map([], |row| row.get(0))
+        .context("Failed to query client names")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read client name row")?;
+
+    Ok(clients)
+}
+
+/// Also used by the log viewer's `stats` command, hence `pub(crate)`.
+pub(crate) fn count_events_by_tool(conn: &Connection) -> Result<Vec<(String, i64)>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT canonical_tool_name, COUNT(*) \
+             FROM tool_call_events \
+             GROUP BY canonical_tool_name \
+             ORDER BY COUNT(*) DESC, canonical_tool_name ASC",
         )
-        .context("Failed to upsert progress snapshot")?;
+        .context("Failed to prepare tool count query")?;
+
+    let counts = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .context("Failed to query tool counts")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read tool count row")?;
+
+    Ok(counts)
+}
+
+pub(super) fn prune(conn: &mut Connection, seconds: u64) -> Result<usize> {
+    let cutoff = unix_epoch_seconds()?.saturating_sub(seconds);
+    let cutoff = i64::try_from(cutoff).context("Retention cutoff out of range")?;
+
+    let tx = conn
+        .transaction()
+        .context("Failed to begin prune transaction")?;
+    let events = tx
+        .execute(
+            "DELETE FROM tool_call_events WHERE CAST(created_at AS INTEGER) < ?1",
+            [cutoff],
+        )
+        .context("Failed to prune tool call events")?;
+    let snapshots = tx
+        .execute(
+            "DELETE FROM progress_snapshots \
+             WHERE CAST(updated_at AS INTEGER) < ?1 \
+             AND NOT EXISTS ( \
+               SELECT 1 FROM tool_call_events e \
+               WHERE e.internal_id = progress_snapshots.internal_id)",
+            [cutoff],
+        )
+        .context("Failed to prune progress snapshots")?;
+    tx.commit().context("Failed to commit prune transaction")?;
+
+    Ok(events + snapshots)
+}
+
+pub(super) fn get_snapshot(
+    conn: &Connection,
+    internal_id: &str,
+) -> Result<Option<ProgressSnapshot>> {
+    conn.query_row(
+        "SELECT CAST(updated_at AS INTEGER), internal_id, event, snapshot_text \
+         FROM progress_snapshots \
+         WHERE internal_id = ?1",
+        [internal_id],
+        |row| {
+            Ok(ProgressSnapshot {
+                updated_at: row.get(0)?,
+                internal_id: row.get(1)?,
+                event: row.get(2)?,
+                snapshot_text: row.get(3)?,
+            })
+        },
+    )
+    .optional()
+    .with_context(|| format!("Failed to query progress snapshot: {}", internal_id))
+}
+
+/// Schema migrations, applied in order; `PRAGMA user_version` records how many have run.
+///
+/// Databases created before versioning have `user_version` 0 and already hold the
+/// first step's tables, which is why it only uses `CREATE ... IF NOT EXISTS`.
+const MIGRATIONS: &[&str] = &[
+    include_str!("schema.sql"),
+    "ALTER TABLE progress_snapshots ADD COLUMN created_at TEXT;
+     UPDATE progress_snapshots SET created_at = updated_at;",
+];
 
-        Ok(())
+pub(super) fn migrate(conn: &mut Connection) -> Result<()> {
+    let tx = conn
+        .transaction()
+        .context("Failed to begin schema migration")?;
+
+    let version: i64 = tx
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .context("Failed to read sqlite schema version")?;
+    let version = usize::try_from(version).context("Invalid sqlite schema version")?;
+    if version > MIGRATIONS.len() {
+        anyhow::bail!(
+            "sqlite schema version {} is newer than supported version {}",
+            version,
+            MIGRATIONS.len()
+        );
+    }
+
+    for (step, sql) in MIGRATIONS.iter().enumerate().skip(version) {
+        tx.execute_batch(sql)
+            .with_context(|| format!("Failed to apply sqlite migration {}", step + 1))?;
     }
+    tx.pragma_update(None, "user_version", MIGRATIONS.len() as i64)
+        .context("Failed to update sqlite schema version")?;
+
+    tx.commit().context("Failed to commit schema migration")
+}
+
+/// Insert an event, using `now` unless the event carries its own `created_at`.
+pub(super) fn insert_event(conn: &Connection, now: &str, event: &ToolCallEvent) -> Result<()> {
+    let created_at = if event.created_at != 0 {
+        event.created_at.to_string()
+    } else {
+        now.to_string()
+    };
+    let is_error = if event.is_error { 1 } else { 0 };
+
+    conn.execute(
+        "INSERT INTO tool_call_events (created_at, transport, client_name, tool_name, canonical_tool_name, request_sexpr, response_sexpr, is_error, internal_id)\
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            created_at,
+            event.transport,
+            event.client_name,
+            event.tool_name,
+            event.canonical_tool_name,
+            event.request_sexpr,
+            event.response_sexpr,
+            is_error,
+            event.internal_id,
+        ],
+    )
+    .context("Failed to insert tool call event")?;
+
+    Ok(())
 }
 
-fn unix_epoch_seconds_string() -> Result<String> {
+fn unix_epoch_seconds() -> Result<u64> {
     let secs = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .context("System time is before UNIX_EPOCH")?
         .as_secs();
-    Ok(secs.to_string())
+    Ok(secs)
+}
+
+pub(super) fn unix_epoch_seconds_string() -> Result<String> {
+    Ok(unix_epoch_seconds()?.to_string())
+}
+
+fn timestamp_or_now(secs: i64) -> Result<String> {
+    if secs != 0 {
+        Ok(secs.to_string())
+    } else {
+        unix_epoch_seconds_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn event(tool_name: &str, is_error: bool) -> ToolCallEvent {
+        ToolCallEvent {
+            created_at: 0,
+            transport: "stdio".to_string(),
+            client_name: Some("tester".to_string()),
+            tool_name: tool_name.to_string(),
+            canonical_tool_name: tool_name.to_string(),
+            request_sexpr: format!("({})", tool_name),
+            response_sexpr: "(success)".to_string(),
+            is_error,
+            internal_id: None,
+        }
+    }
+
+    #[test]
+    fn test_list_tool_call_events() {
+        let db = SqlitePersistence::open_in_memory().unwrap();
+        db.insert_tool_call_event(&event("first", false)).unwrap();
+        db.insert_tool_call_event(&event("second", true)).unwrap();
+
+        let events = db.list_tool_call_events(None).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].tool_name, "second");
+        assert!(events[0].is_error);
+        assert_eq!(events[1].client_name.as_deref(), Some("tester"));
+        assert!(events[1].created_at > 0);
+
+        let events = db.list_tool_call_events(Some(1)).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].tool_name, "second");
+    }
+
+    #[test]
+    fn test_get_progress_snapshot() {
+        let db = SqlitePersistence::open_in_memory().unwrap();
+        db.upsert_progress_snapshot(&ProgressSnapshot {
+            updated_at: 0,
+            internal_id: "abc".to_string(),
+            event: "started".to_string(),
+            snapshot_text: "(progress)".to_string(),
+        })
+        .unwrap();
+
+        let snapshot = db.get_progress_snapshot("abc").unwrap().unwrap();
+        assert_eq!(snapshot.event, "started");
+        assert_eq!(snapshot.snapshot_text, "(progress)");
+        assert!(snapshot.updated_at > 0);
+
+        assert!(db.get_progress_snapshot("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_insert_tool_call_events_batch() {
+        let db = SqlitePersistence::open_in_memory().unwrap();
+
+        let batch = [event("a", false), event("b", false), event("c", true)];
+        db.insert_tool_call_events(&batch).unwrap();
+
+        assert_eq!(db.list_tool_call_events(None).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_insert_tool_call_events_rolls_back() {
+        let db = SqlitePersistence::open_in_memory().unwrap();
+        db.conn
+            .lock()
+            .unwrap()
+            .execute_batch(
+                "CREATE TRIGGER reject_boom BEFORE INSERT ON tool_call_events \
+                 WHEN NEW.tool_name = 'boom' \
+                 BEGIN SELECT RAISE(ABORT, 'boom rejected'); END;",
+            )
+            .unwrap();
+
+        let batch = [event("a", false), event("boom", false), event("c", false)];
+        assert!(db.insert_tool_call_events(&batch).is_err());
+
+        assert!(db.list_tool_call_events(None).unwrap().is_empty());
+    }
+
+    fn journal_mode(db: &SqlitePersistence) -> String {
+        db.conn
+            .lock()
+            .unwrap()
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_open_enables_wal() {
+        let dir = tempdir().unwrap();
+        let db = SqlitePersistence::open(&dir.path().join("log.db")).unwrap();
+        assert_eq!(journal_mode(&db), "wal");
+    }
+
+    #[test]
+    fn test_open_with_options_without_wal() {
+        let dir = tempdir().unwrap();
+        let options = PersistenceOptions {
+            wal: false,
+            ..PersistenceOptions::default()
+        };
+        let db = SqlitePersistence::open_with_options(&dir.path().join("log.db"), options).unwrap();
+        assert_eq!(journal_mode(&db), "delete");
+    }
+
+    #[test]
+    fn test_open_migrates_unversioned_db() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("log.db");
+        {
+            // A database written before schema versioning existed
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(MIGRATIONS[0]).unwrap();
+            conn.execute(
+                "INSERT INTO progress_snapshots (internal_id, updated_at, event, snapshot_text) \
+                 VALUES ('old', '100', 'started', '(progress)')",
+                [],
+            )
+            .unwrap();
+        }
+
+        let db = SqlitePersistence::open(&path).unwrap();
+        {
+            let conn = db.conn.lock().unwrap();
+            let version: i64 = conn
+                .query_row("PRAGMA user_version", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(version, MIGRATIONS.len() as i64);
+            let created_at: String = conn
+                .query_row(
+                    "SELECT created_at FROM progress_snapshots WHERE internal_id = 'old'",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert_eq!(created_at, "100");
+        }
+
+        let snapshot = db.get_progress_snapshot("old").unwrap().unwrap();
+        assert_eq!(snapshot.updated_at, 100);
+        assert_eq!(snapshot.event, "started");
+        drop(db);
+
+        // Reopening an up-to-date database applies nothing
+        SqlitePersistence::open(&path).unwrap();
+    }
+
+    #[test]
+    fn test_prune_older_than() {
+        let db = SqlitePersistence::open_in_memory().unwrap();
+
+        let mut recent = event("recent", false);
+        recent.internal_id = Some("kept".to_string());
+        db.insert_tool_call_event(&recent).unwrap();
+        {
+            let conn = db.conn.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO tool_call_events (created_at, transport, tool_name, canonical_tool_name, \
+                   request_sexpr, response_sexpr, is_error, internal_id) \
+                 VALUES ('100', 'stdio', 'old', 'old', '(old)', '(success)', 0, 'orphan'); \
+                 INSERT INTO progress_snapshots (internal_id, updated_at, event, snapshot_text) \
+                 VALUES ('orphan', '100', 'done', '(progress)'); \
+                 INSERT INTO progress_snapshots (internal_id, updated_at, event, snapshot_text) \
+                 VALUES ('kept', '100', 'running', '(progress)');",
+            )
+            .unwrap();
+        }
+
+        // The old event and the snapshot it leaves orphaned go; the rest stays
+        assert_eq!(db.prune_older_than(3600).unwrap(), 2);
+
+        let events = db.list_tool_call_events(None).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].tool_name, "recent");
+        assert!(db.get_progress_snapshot("orphan").unwrap().is_none());
+        assert!(db.get_progress_snapshot("kept").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_events_between() {
+        let db = SqlitePersistence::open_in_memory().unwrap();
+
+        let mut batch = Vec::new();
+        for (name, created_at) in [("late", 300), ("early", 100), ("middle", 200)] {
+            let mut e = event(name, false);
+            e.created_at = created_at;
+            batch.push(e);
+        }
+        db.insert_tool_call_events(&batch).unwrap();
+
+        let events = db.events_between(150, 300).unwrap();
+        let names: Vec<&str> = events.iter().map(|e| e.tool_name.as_str()).collect();
+        assert_eq!(names, vec!["middle", "late"]);
+        assert_eq!(events[0].created_at, 200);
+
+        assert_eq!(db.events_between(100, 100).unwrap().len(), 1);
+        assert!(db.events_between(301, 400).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_counts_by_tool() {
+        let db = SqlitePersistence::open_in_memory().unwrap();
+        assert!(db.counts_by_tool().unwrap().is_empty());
+
+        let mut aliased = event("e", false);
+        aliased.canonical_tool_name = "echo".to_string();
+        let batch = [event("build", true), event("echo", false), aliased];
+        db.insert_tool_call_events(&batch).unwrap();
+
+        assert_eq!(
+            db.counts_by_tool().unwrap(),
+            vec![("echo".to_string(), 2), ("build".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn test_client_queries() {
+        let db = SqlitePersistence::open_in_memory().unwrap();
+
+        let mut alice = event("echo", false);
+        alice.client_name = Some("alice".to_string());
+        let mut bob = event("build", false);
+        bob.client_name = Some("bob".to_string());
+        let mut anonymous = event("echo", false);
+        anonymous.client_name = None;
+        let mut alice_later = event("build", true);
+        alice_later.client_name = Some("alice".to_string());
+        db.insert_tool_call_events(&[bob, alice, anonymous, alice_later])
+            .unwrap();
+
+        assert_eq!(db.distinct_clients().unwrap(), vec!["alice", "bob"]);
+
+        let events = db.events_for_client("alice").unwrap();
+        let names: Vec<&str> = events.iter().map(|e| e.tool_name.as_str()).collect();
+        assert_eq!(names, vec!["build", "echo"]);
+        assert_eq!(db.events_for_client("bob").unwrap().len(), 1);
+        assert!(db.events_for_client("carol").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_poisoned_lock_returns_error() {
+        let db = SqlitePersistence::open_in_memory().unwrap();
+
+        let poisoner = db.clone();
+        let result = std::thread::spawn(move || {
+            let _conn = poisoner.conn.lock().unwrap();
+            panic!("writer panicked mid-insert");
+        })
+        .join();
+        assert!(result.is_err());
+
+        let err = db
+            .insert_tool_call_event(&event("echo", false))
+            .unwrap_err();
+        assert!(err.to_string().contains("lock poisoned"));
+        assert!(db.list_tool_call_events(None).is_err());
+    }
+
+    #[test]
+    fn test_open_in_memory() {
+        let db = SqlitePersistence::open_in_memory().unwrap();
+        db.insert_tool_call_event(&event("echo", false)).unwrap();
+
+        let events = db.list_tool_call_events(None).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].tool_name, "echo");
+    }
+
+    #[test]
+    fn test_supplied_timestamps_round_trip() {
+        let db = SqlitePersistence::open_in_memory().unwrap();
+
+        let mut supplied = event("echo", false);
+        supplied.created_at = 1_700_000_000;
+        db.insert_tool_call_event(&supplied).unwrap();
+        db.insert_tool_call_event(&event("now", false)).unwrap();
+
+        let events = db.list_tool_call_events(None).unwrap();
+        assert_eq!(events[0].tool_name, "now");
+        assert!(events[0].created_at > 1_700_000_000);
+        assert_eq!(events[1].created_at, 1_700_000_000);
+
+        db.upsert_progress_snapshot(&ProgressSnapshot {
+            updated_at: 1_700_000_123,
+            internal_id: "abc".to_string(),
+            event: "started".to_string(),
+            snapshot_text: "(progress)".to_string(),
+        })
+        .unwrap();
+        let snapshot = db.get_progress_snapshot("abc").unwrap().unwrap();
+        assert_eq!(snapshot.updated_at, 1_700_000_123);
+    }
 }
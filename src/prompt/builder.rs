@@ -3,8 +3,11 @@
 //! This module combines configuration and markdown extraction to build prompts.
 
 use super::config::{Config, ConfigResult, InitializeConfig, ToolConfig};
-use super::markdown::load_and_extract;
+use super::markdown::{extract_section, extract_sections, MarkdownError};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::SystemTime;
 use thiserror::Error;
 
 /// Errors that can occur during prompt building.
@@ -17,15 +20,44 @@ pub enum PromptError {
     /// Markdown extraction error
     #[error("Markdown error: {0}")]
     MarkdownError(#[from] crate::prompt::markdown::MarkdownError),
+
+    /// Tool aliases that point back at themselves
+    #[error("Alias cycle: {0}")]
+    AliasCycle(String),
+
+    /// A `prompt_doc` file that exists in none of the docs directories
+    #[error("Doc not found: {doc} (searched: {searched})")]
+    DocNotFound {
+        /// The configured `prompt_doc`
+        doc: String,
+        /// The directories tried, in order
+        searched: String,
+    },
 }
 
 /// Result type for prompt operations.
 pub type PromptResult<T> = Result<T, PromptError>;
 
+/// A markdown file read by the builder, with its modification time at read time
+struct CachedDoc {
+    content: String,
+    modified: Option<SystemTime>,
+}
+
 /// Prompt builder that loads configuration and extracts markdown sections
 pub struct PromptBuilder {
     config: Config,
-    docs_dir: PathBuf,
+    config_path: PathBuf,
+    config_modified: Option<SystemTime>,
+    /// Directories searched in order for each `prompt_doc`
+    docs_dirs: Vec<PathBuf>,
+    /// Markdown files already read, keyed by resolved path
+    doc_cache: Mutex<HashMap<PathBuf, CachedDoc>>,
+}
+
+/// Modification time of a file, if it can be determined
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
 }
 
 impl PromptBuilder {
@@ -33,13 +65,46 @@ impl PromptBuilder {
     /// config_path: Path to tools.toml
     /// docs_dir: Directory containing the markdown documentation files
     pub fn new(config_path: impl AsRef<Path>, docs_dir: impl AsRef<Path>) -> ConfigResult<Self> {
-        let config = Config::from_file(config_path)?;
+        Self::new_with_dirs(config_path, &[docs_dir.as_ref().to_path_buf()])
+    }
+
+    /// Create a prompt builder that looks for docs in several directories
+    ///
+    /// Each `prompt_doc` is taken from the first directory that contains it,
+    /// so list project-local docs before a shared base set to overlay them.
+    pub fn new_with_dirs(
+        config_path: impl AsRef<Path>,
+        docs_dirs: &[PathBuf],
+    ) -> ConfigResult<Self> {
+        let config_path = config_path.as_ref().to_path_buf();
+        let config_modified = modified_time(&config_path);
+        let config = Config::from_file(&config_path)?;
         Ok(Self {
             config,
-            docs_dir: docs_dir.as_ref().to_path_buf(),
+            config_path,
+            config_modified,
+            docs_dirs: docs_dirs.to_vec(),
+            doc_cache: Mutex::new(HashMap::new()),
         })
     }
 
+    /// Path of `prompt_doc` in the first docs directory that has it
+    fn find_doc(&self, prompt_doc: &str) -> PromptResult<PathBuf> {
+        self.docs_dirs
+            .iter()
+            .map(|dir| dir.join(prompt_doc))
+            .find(|path| path.is_file())
+            .ok_or_else(|| PromptError::DocNotFound {
+                doc: prompt_doc.to_string(),
+                searched: self
+                    .docs_dirs
+                    .iter()
+                    .map(|dir| dir.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            })
+    }
+
     /// Build the initialize prompt
     pub fn build_initialize_prompt(&self) -> PromptResult<String> {
         let init_config = &self.config.initialize;
@@ -47,23 +112,179 @@ impl PromptBuilder {
     }
 
     /// Build a tool prompt
+    ///
+    /// If the tool is an alias (`alias_for`), the canonical tool's prompt is built.
     pub fn build_tool_prompt(&self, tool_name: &str) -> PromptResult<String> {
-        let tool_config = self.config.get_tool(tool_name)?;
+        let tool_config = self.resolve_tool_config(tool_name)?;
         self.build_prompt_from_tool_config(tool_config)
     }
 
+    /// Build the prompt of every configured tool, sorted by tool name
+    ///
+    /// Aliases are included under their own name with the canonical tool's
+    /// prompt. With `skip_errors`, tools whose prompt cannot be built are
+    /// left out; otherwise the first failure is returned.
+    pub fn build_all_tool_prompts(&self, skip_errors: bool) -> PromptResult<Vec<(String, String)>> {
+        let mut tool_names = self.get_tool_names();
+        tool_names.sort();
+
+        let mut prompts = Vec::with_capacity(tool_names.len());
+        for tool_name in tool_names {
+            match self.build_tool_prompt(&tool_name) {
+                Ok(prompt) => prompts.push((tool_name, prompt)),
+                Err(_) if skip_errors => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(prompts)
+    }
+
+    /// Follow `alias_for` links to the canonical tool configuration
+    fn resolve_tool_config(&self, tool_name: &str) -> PromptResult<&ToolConfig> {
+        let mut chain = vec![tool_name.to_string()];
+        let mut config = self.config.get_tool(tool_name)?;
+        while let Some(target) = &config.alias_for {
+            let seen = chain.contains(target);
+            chain.push(target.clone());
+            if seen {
+                return Err(PromptError::AliasCycle(chain.join(" -> ")));
+            }
+            config = self.config.get_tool(target)?;
+        }
+        Ok(config)
+    }
+
     /// Build prompt from initialize configuration
     fn build_prompt_from_init_config(&self, config: &InitializeConfig) -> PromptResult<String> {
-        let doc_path = self.docs_dir.join(&config.prompt_doc);
-        let content = load_and_extract(&doc_path, &config.prompt_sections)?;
-        Ok(content)
+        self.load_and_extract_cached(&config.prompt_doc, &config.prompt_sections)
     }
 
     /// Build prompt from a tool configuration
     fn build_prompt_from_tool_config(&self, config: &ToolConfig) -> PromptResult<String> {
-        let doc_path = self.docs_dir.join(&config.prompt_doc);
-        let content = load_and_extract(&doc_path, &config.prompt_sections)?;
-        Ok(content)
+        self.load_and_extract_cached(&config.prompt_doc, &config.prompt_sections)
+    }
+
+    /// Extract sections from a doc file, reading the file only on first use
+    fn load_and_extract_cached(
+        &self,
+        prompt_doc: &str,
+        sections: &[String],
+    ) -> PromptResult<String> {
+        self.with_cached_doc(prompt_doc, |content| {
+            extract_sections(content, sections).map_err(PromptError::from)
+        })
+    }
+
+    /// Run `f` on the contents of a doc file, reading the file only on first use
+    fn with_cached_doc<T>(
+        &self,
+        prompt_doc: &str,
+        f: impl FnOnce(&str) -> PromptResult<T>,
+    ) -> PromptResult<T> {
+        let doc_path = self.find_doc(prompt_doc)?;
+        let mut cache = self.lock_cache();
+        if !cache.contains_key(&doc_path) {
+            let modified = modified_time(&doc_path);
+            let content = std::fs::read_to_string(&doc_path).map_err(MarkdownError::from)?;
+            cache.insert(doc_path.clone(), CachedDoc { content, modified });
+        }
+        f(&cache[&doc_path].content)
+    }
+
+    fn lock_cache(&self) -> MutexGuard<'_, HashMap<PathBuf, CachedDoc>> {
+        // The cache holds plain strings, so a poisoned lock is still usable
+        self.doc_cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Forget all cached markdown files so the next build re-reads them from disk
+    pub fn clear_cache(&self) {
+        self.lock_cache().clear();
+    }
+
+    /// Check that every configured doc file and section exists
+    ///
+    /// Unlike building prompts, this does not stop at the first failure: it
+    /// returns one message per problem, and an empty list when the
+    /// configuration is healthy. Useful as a startup check.
+    pub fn validate(&self) -> PromptResult<Vec<String>> {
+        let mut problems = Vec::new();
+
+        let init = &self.config.initialize;
+        self.check_doc(
+            "initialize",
+            &init.prompt_doc,
+            &init.prompt_sections,
+            &mut problems,
+        );
+
+        let mut tool_names = self.get_tool_names();
+        tool_names.sort();
+        for tool_name in tool_names {
+            let owner = format!("tool {}", tool_name);
+            let config = &self.config.tools[&tool_name];
+            if config.alias_for.is_some() {
+                // The canonical tool's docs are checked under its own name
+                if let Err(e) = self.resolve_tool_config(&tool_name) {
+                    problems.push(format!("{}: {}", owner, e));
+                }
+                continue;
+            }
+            self.check_doc(
+                &owner,
+                &config.prompt_doc,
+                &config.prompt_sections,
+                &mut problems,
+            );
+        }
+
+        Ok(problems)
+    }
+
+    /// Record a problem for the doc file if it is unreadable and for each missing section
+    fn check_doc(
+        &self,
+        owner: &str,
+        prompt_doc: &str,
+        sections: &[String],
+        problems: &mut Vec<String>,
+    ) {
+        let result = self.with_cached_doc(prompt_doc, |content| {
+            for heading in sections {
+                if let Err(e) = extract_section(content, heading) {
+                    problems.push(format!("{}: {} in {}", owner, e, prompt_doc));
+                }
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            problems.push(format!("{}: cannot read {}: {}", owner, prompt_doc, e));
+        }
+    }
+
+    /// Re-read the configuration from its original path and clear the doc cache
+    ///
+    /// On error the previous configuration is kept.
+    pub fn reload(&mut self) -> PromptResult<()> {
+        let modified = modified_time(&self.config_path);
+        self.config = Config::from_file(&self.config_path)?;
+        self.config_modified = modified;
+        self.clear_cache();
+        Ok(())
+    }
+
+    /// Reload if the config file or any cached doc file changed on disk
+    ///
+    /// Returns `true` if a reload happened.
+    pub fn reload_if_changed(&mut self) -> PromptResult<bool> {
+        let docs_changed = self
+            .lock_cache()
+            .iter()
+            .any(|(path, doc)| modified_time(path) != doc.modified);
+        if !docs_changed && modified_time(&self.config_path) == self.config_modified {
+            return Ok(false);
+        }
+        self.reload()?;
+        Ok(true)
     }
 
     /// Get all tool names from configuration
@@ -101,7 +322,7 @@ mod tests {
         let mut md_file = std::fs::File::create(&md_path).unwrap();
         writeln!(md_file, "# Section 1").unwrap();
         writeln!(md_file, "Content 1").unwrap();
-        writeln!(md_file, "").unwrap();
+        writeln!(md_file).unwrap();
         writeln!(md_file, "## Section 2").unwrap();
         writeln!(md_file, "Content 2").unwrap();
 
@@ -111,7 +332,7 @@ mod tests {
         writeln!(config_file, "[initialize]").unwrap();
         writeln!(config_file, "prompt_doc = \"test.md\"").unwrap();
         writeln!(config_file, "prompt_sections = [\"# Section 1\"]").unwrap();
-        writeln!(config_file, "").unwrap();
+        writeln!(config_file).unwrap();
         writeln!(config_file, "[tools.test-tool]").unwrap();
         writeln!(config_file, "prompt_doc = \"test.md\"").unwrap();
         writeln!(config_file, "prompt_sections = [\"## Section 2\"]").unwrap();
@@ -141,6 +362,240 @@ mod tests {
         assert!(prompt.contains("Content 2"));
     }
 
+    fn append_config(config_path: &Path, extra: &str) {
+        let mut config_file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(config_path)
+            .unwrap();
+        writeln!(config_file, "{}", extra).unwrap();
+    }
+
+    #[test]
+    fn test_prompt_builder_alias() {
+        let (_temp_dir, config_path, docs_dir) = create_test_setup();
+        append_config(
+            &config_path,
+            "[tools.alias-tool]\nalias_for = \"test-tool\"",
+        );
+
+        let builder = PromptBuilder::new(&config_path, &docs_dir).unwrap();
+        let prompt = builder.build_tool_prompt("alias-tool").unwrap();
+
+        assert_eq!(prompt, builder.build_tool_prompt("test-tool").unwrap());
+    }
+
+    #[test]
+    fn test_prompt_builder_alias_missing_target() {
+        let (_temp_dir, config_path, docs_dir) = create_test_setup();
+        append_config(&config_path, "[tools.alias-tool]\nalias_for = \"missing\"");
+
+        let builder = PromptBuilder::new(&config_path, &docs_dir).unwrap();
+        let err = builder.build_tool_prompt("alias-tool").unwrap_err();
+
+        assert!(matches!(
+            err,
+            PromptError::ConfigError(crate::prompt::config::ConfigError::MissingConfig(ref name))
+                if name == "missing"
+        ));
+    }
+
+    #[test]
+    fn test_prompt_builder_alias_cycle() {
+        let (_temp_dir, config_path, docs_dir) = create_test_setup();
+        append_config(
+            &config_path,
+            "[tools.a]\nalias_for = \"b\"\n\n[tools.b]\nalias_for = \"a\"",
+        );
+
+        let builder = PromptBuilder::new(&config_path, &docs_dir).unwrap();
+        let err = builder.build_tool_prompt("a").unwrap_err();
+
+        assert!(matches!(err, PromptError::AliasCycle(_)));
+        assert_eq!(err.to_string(), "Alias cycle: a -> b -> a");
+    }
+
+    #[test]
+    fn test_prompt_builder_caches_docs() {
+        let (_temp_dir, config_path, docs_dir) = create_test_setup();
+
+        let builder = PromptBuilder::new(&config_path, &docs_dir).unwrap();
+        let first = builder.build_tool_prompt("test-tool").unwrap();
+
+        // Edits on disk are not seen until the cache is cleared
+        std::fs::write(docs_dir.join("test.md"), "## Section 2\nEdited\n").unwrap();
+        assert_eq!(builder.build_tool_prompt("test-tool").unwrap(), first);
+        assert!(builder
+            .build_initialize_prompt()
+            .unwrap()
+            .contains("Content 1"));
+
+        builder.clear_cache();
+        let reloaded = builder.build_tool_prompt("test-tool").unwrap();
+        assert!(reloaded.contains("Edited"));
+        assert!(!reloaded.contains("Content 2"));
+    }
+
+    fn touch_later(path: &Path) {
+        let later = SystemTime::now() + std::time::Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_prompt_builder_reload() {
+        let (_temp_dir, config_path, docs_dir) = create_test_setup();
+
+        let mut builder = PromptBuilder::new(&config_path, &docs_dir).unwrap();
+        assert!(!builder.get_tool_names().contains(&"new-tool".to_string()));
+
+        append_config(
+            &config_path,
+            "[tools.new-tool]\nprompt_doc = \"test.md\"\nprompt_sections = [\"# Section 1\"]",
+        );
+        builder.reload().unwrap();
+
+        assert!(builder.get_tool_names().contains(&"new-tool".to_string()));
+        let prompt = builder.build_tool_prompt("new-tool").unwrap();
+        assert!(prompt.contains("Content 1"));
+    }
+
+    #[test]
+    fn test_prompt_builder_reload_if_changed() {
+        let (_temp_dir, config_path, docs_dir) = create_test_setup();
+
+        let mut builder = PromptBuilder::new(&config_path, &docs_dir).unwrap();
+        builder.build_tool_prompt("test-tool").unwrap();
+        assert!(!builder.reload_if_changed().unwrap());
+
+        let doc_path = docs_dir.join("test.md");
+        std::fs::write(&doc_path, "## Section 2\nEdited\n").unwrap();
+        touch_later(&doc_path);
+        assert!(builder.reload_if_changed().unwrap());
+        assert!(builder
+            .build_tool_prompt("test-tool")
+            .unwrap()
+            .contains("Edited"));
+
+        append_config(&config_path, "[tools.new-tool]\nalias_for = \"test-tool\"");
+        touch_later(&config_path);
+        assert!(builder.reload_if_changed().unwrap());
+        assert!(builder.get_tool_names().contains(&"new-tool".to_string()));
+    }
+
+    #[test]
+    fn test_validate_clean_config() {
+        let (_temp_dir, config_path, docs_dir) = create_test_setup();
+
+        let builder = PromptBuilder::new(&config_path, &docs_dir).unwrap();
+        assert!(builder.validate().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_validate_reports_all_problems() {
+        let (_temp_dir, config_path, docs_dir) = create_test_setup();
+        append_config(
+            &config_path,
+            "[tools.typo-tool]\nprompt_doc = \"test.md\"\nprompt_sections = [\"## Sectoin 2\"]\n\n\
+             [tools.missing-doc]\nprompt_doc = \"nope.md\"\nprompt_sections = [\"# Intro\"]",
+        );
+
+        let builder = PromptBuilder::new(&config_path, &docs_dir).unwrap();
+        let problems = builder.validate().unwrap();
+
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].starts_with("tool missing-doc: cannot read nope.md"));
+        assert_eq!(
+            problems[1],
+            "tool typo-tool: Section not found: ## Sectoin 2 in test.md"
+        );
+    }
+
+    #[test]
+    fn test_build_all_tool_prompts() {
+        let (_temp_dir, config_path, docs_dir) = create_test_setup();
+        append_config(
+            &config_path,
+            "[tools.a-tool]\nprompt_doc = \"test.md\"\nprompt_sections = [\"# Section 1\"]\n\n\
+             [tools.z-alias]\nalias_for = \"test-tool\"",
+        );
+
+        let builder = PromptBuilder::new(&config_path, &docs_dir).unwrap();
+        let prompts = builder.build_all_tool_prompts(false).unwrap();
+
+        let names: Vec<&str> = prompts.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["a-tool", "test-tool", "z-alias"]);
+        assert!(prompts[0].1.contains("Content 1"));
+        assert!(prompts[1].1.contains("Content 2"));
+        assert_eq!(prompts[2].1, prompts[1].1);
+    }
+
+    #[test]
+    fn test_build_all_tool_prompts_errors() {
+        let (_temp_dir, config_path, docs_dir) = create_test_setup();
+        append_config(
+            &config_path,
+            "[tools.broken]\nprompt_doc = \"nope.md\"\nprompt_sections = [\"# Intro\"]",
+        );
+
+        let builder = PromptBuilder::new(&config_path, &docs_dir).unwrap();
+        assert!(builder.build_all_tool_prompts(false).is_err());
+
+        let prompts = builder.build_all_tool_prompts(true).unwrap();
+        assert_eq!(prompts.len(), 1);
+        assert_eq!(prompts[0].0, "test-tool");
+    }
+
+    #[test]
+    fn test_docs_dirs_precedence() {
+        let (temp_dir, config_path, docs_dir) = create_test_setup();
+        let local_dir = temp_dir.path().join("local");
+        std::fs::create_dir(&local_dir).unwrap();
+        std::fs::write(local_dir.join("test.md"), "## Section 2\nLocal\n").unwrap();
+        std::fs::write(docs_dir.join("extra.md"), "## Extra\nShared only\n").unwrap();
+        append_config(
+            &config_path,
+            "[tools.extra-tool]\nprompt_doc = \"extra.md\"\nprompt_sections = [\"## Extra\"]",
+        );
+
+        let builder =
+            PromptBuilder::new_with_dirs(&config_path, &[local_dir.clone(), docs_dir.clone()])
+                .unwrap();
+        // Found in the first directory, overriding the shared copy
+        assert!(builder
+            .build_tool_prompt("test-tool")
+            .unwrap()
+            .contains("Local"));
+        // Found only in the second directory
+        assert!(builder
+            .build_tool_prompt("extra-tool")
+            .unwrap()
+            .contains("Shared only"));
+    }
+
+    #[test]
+    fn test_docs_dirs_missing_everywhere() {
+        let (temp_dir, config_path, docs_dir) = create_test_setup();
+        let local_dir = temp_dir.path().join("local");
+        append_config(
+            &config_path,
+            "[tools.missing]\nprompt_doc = \"nope.md\"\nprompt_sections = [\"# Intro\"]",
+        );
+
+        let builder =
+            PromptBuilder::new_with_dirs(&config_path, &[local_dir.clone(), docs_dir.clone()])
+                .unwrap();
+        let err = builder.build_tool_prompt("missing").unwrap_err();
+        assert!(matches!(err, PromptError::DocNotFound { .. }));
+        let message = err.to_string();
+        assert!(message.contains("nope.md"));
+        assert!(message.contains(&local_dir.display().to_string()));
+        assert!(message.contains(&docs_dir.display().to_string()));
+    }
+
     #[test]
     fn test_get_tool_names() {
         let (_temp_dir, config_path, docs_dir) = create_test_setup();
@@ -30,9 +30,11 @@ pub type ConfigResult<T> = Result<T, ConfigError>;
 /// Configuration for a single tool
 #[derive(Debug, Clone, Deserialize)]
 pub struct ToolConfig {
-    /// Path to the documentation file
+    /// Path to the documentation file (may be omitted for aliases)
+    #[serde(default)]
     pub prompt_doc: String,
-    /// List of section headings to extract
+    /// List of section headings to extract (may be omitted for aliases)
+    #[serde(default)]
     pub prompt_sections: Vec<String>,
     /// Optional alias pointing to the canonical tool name
     #[serde(default)]
@@ -89,13 +91,27 @@ impl Config {
         &self,
         key: &str,
     ) -> ConfigResult<Option<T>> {
-        if let Some(value) = self.extra.get(key) {
-            let result = T::deserialize(value.clone())
-                .map_err(|e| ConfigError::TomlError(e))?;
-            Ok(Some(result))
-        } else {
-            Ok(None)
-        }
+        get_typed(&self.extra, key)
+    }
+}
+
+impl ToolConfig {
+    /// Get a tool-specific extra field (e.g. `timeout_ms`) as a typed value
+    pub fn get_extra<T: serde::de::DeserializeOwned>(&self, key: &str) -> ConfigResult<Option<T>> {
+        get_typed(&self.extra, key)
+    }
+}
+
+/// Deserialize the value stored under `key` in a table of extra fields
+fn get_typed<T: serde::de::DeserializeOwned>(
+    extra: &HashMap<String, toml::Value>,
+    key: &str,
+) -> ConfigResult<Option<T>> {
+    if let Some(value) = extra.get(key) {
+        let result = T::deserialize(value.clone()).map_err(ConfigError::TomlError)?;
+        Ok(Some(result))
+    } else {
+        Ok(None)
     }
 }
 
@@ -115,11 +131,11 @@ mod tests {
             "prompt_sections = [\"# Overview\", \"## Usage\"]"
         )
         .unwrap();
-        writeln!(file, "").unwrap();
+        writeln!(file).unwrap();
         writeln!(file, "[tools.my-tool]").unwrap();
         writeln!(file, "prompt_doc = \"api-spec.md\"").unwrap();
         writeln!(file, "prompt_sections = [\"### 1. my-tool\"]").unwrap();
-        writeln!(file, "").unwrap();
+        writeln!(file).unwrap();
         writeln!(file, "[tools.another-tool]").unwrap();
         writeln!(file, "prompt_doc = \"api-spec.md\"").unwrap();
         writeln!(file, "prompt_sections = [\"### 2. another-tool\"]").unwrap();
@@ -141,7 +157,7 @@ mod tests {
         writeln!(file, "[initialize]").unwrap();
         writeln!(file, "prompt_doc = \"spec.md\"").unwrap();
         writeln!(file, "prompt_sections = [\"# Overview\"]").unwrap();
-        writeln!(file, "").unwrap();
+        writeln!(file).unwrap();
         writeln!(file, "[tools]").unwrap();
         file.flush().unwrap();
 
@@ -156,9 +172,9 @@ mod tests {
         writeln!(file, "[initialize]").unwrap();
         writeln!(file, "prompt_doc = \"spec.md\"").unwrap();
         writeln!(file, "prompt_sections = [\"# Spec\"]").unwrap();
-        writeln!(file, "").unwrap();
+        writeln!(file).unwrap();
         writeln!(file, "[tools]").unwrap();
-        writeln!(file, "").unwrap();
+        writeln!(file).unwrap();
         writeln!(file, "[my_app]").unwrap();
         writeln!(file, "max_retries = 3").unwrap();
         file.flush().unwrap();
@@ -174,4 +190,27 @@ mod tests {
         assert!(custom.is_some());
         assert_eq!(custom.unwrap().max_retries, 3);
     }
+
+    #[test]
+    fn test_tool_extra_field() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "[initialize]").unwrap();
+        writeln!(file, "prompt_doc = \"spec.md\"").unwrap();
+        writeln!(file, "prompt_sections = [\"# Spec\"]").unwrap();
+        writeln!(file).unwrap();
+        writeln!(file, "[tools.slow-tool]").unwrap();
+        writeln!(file, "prompt_doc = \"api-spec.md\"").unwrap();
+        writeln!(file, "prompt_sections = [\"## slow-tool\"]").unwrap();
+        writeln!(file, "timeout_ms = 5000").unwrap();
+        file.flush().unwrap();
+
+        let config = Config::from_file(file.path()).unwrap();
+        let tool_config = config.get_tool("slow-tool").unwrap();
+
+        let timeout: Option<u64> = tool_config.get_extra("timeout_ms").unwrap();
+        assert_eq!(timeout, Some(5000));
+        let missing: Option<u64> = tool_config.get_extra("retries").unwrap();
+        assert_eq!(missing, None);
+        assert!(tool_config.get_extra::<String>("timeout_ms").is_err());
+    }
 }
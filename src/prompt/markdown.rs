@@ -15,43 +15,244 @@ pub enum MarkdownError {
     /// Requested section not found in markdown
     #[error("Section not found: {0}")]
     SectionNotFound(String),
+
+    /// Requested heading matches more than one section
+    #[error("Ambiguous section: {0}")]
+    AmbiguousSection(String),
 }
 
 /// Result type for markdown operations.
 pub type MarkdownResult<T> = Result<T, MarkdownError>;
 
+/// A heading found in markdown content
+struct Heading<'a> {
+    /// Heading level: the number of `#`, or 1 for `===` and 2 for `---` underlines
+    level: usize,
+    /// The heading line, trimmed (the text line for setext headings)
+    line: &'a str,
+    /// Number of lines the heading occupies (2 for setext headings)
+    span: usize,
+}
+
+impl Heading<'_> {
+    /// Whether `section_heading` names this heading
+    /// Setext headings also match their ATX spelling (`# Title` for `Title` over `===`)
+    fn matches(&self, section_heading: &str) -> bool {
+        let wanted = section_heading.trim();
+        if self.line == wanted {
+            return true;
+        }
+        self.span == 2 && wanted == format!("{} {}", "#".repeat(self.level), self.line)
+    }
+
+    /// The heading text without `#` markers
+    fn text(&self) -> &str {
+        self.line.trim_start_matches('#').trim()
+    }
+}
+
+/// Whether a trimmed line is a setext underline (`===` or `---`)
+fn is_setext_underline(trimmed: &str) -> bool {
+    !trimmed.is_empty() && (trimmed.chars().all(|c| c == '=') || trimmed.chars().all(|c| c == '-'))
+}
+
+/// Number of lines taken by a leading `---` front-matter block, or 0 if there is none
+fn front_matter_len(lines: &[&str]) -> usize {
+    if lines.first().map(|line| line.trim()) != Some("---") {
+        return 0;
+    }
+    lines[1..]
+        .iter()
+        .position(|line| matches!(line.trim(), "---" | "..."))
+        .map(|i| i + 2)
+        .unwrap_or(0)
+}
+
+/// The run of backticks or tildes opening or closing a fenced code block, if `trimmed` is one
+fn code_fence(trimmed: &str) -> Option<&str> {
+    let marker = trimmed.chars().next().filter(|&c| c == '`' || c == '~')?;
+    let len = trimmed.chars().take_while(|&c| c == marker).count();
+    (len >= 3).then(|| &trimmed[..len])
+}
+
+/// Find all ATX (`## Title`) and setext (`Title` over `===`/`---`) headings with their line index
+/// A `---` after a blank line is a horizontal rule, not a heading
+/// Lines inside fenced code blocks (```` ``` ```` or `~~~`) are never headings
+fn find_headings<'a>(lines: &[&'a str]) -> Vec<(usize, Heading<'a>)> {
+    let mut headings = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut idx = front_matter_len(lines);
+    while idx < lines.len() {
+        let trimmed = lines[idx].trim();
+        if let Some(open) = fence {
+            // A closing fence repeats the opening marker, at least as long, with no info string
+            if code_fence(trimmed).is_some_and(|close| close == trimmed && close.starts_with(open))
+            {
+                fence = None;
+            }
+        } else if let Some(open) = code_fence(trimmed) {
+            fence = Some(open);
+        } else if trimmed.starts_with('#') {
+            let level = trimmed.chars().take_while(|&c| c == '#').count();
+            headings.push((
+                idx,
+                Heading {
+                    level,
+                    line: trimmed,
+                    span: 1,
+                },
+            ));
+        } else if !trimmed.is_empty() && !is_setext_underline(trimmed) {
+            let underline = lines.get(idx + 1).map(|line| line.trim()).unwrap_or("");
+            if is_setext_underline(underline) {
+                let level = if underline.starts_with('=') { 1 } else { 2 };
+                headings.push((
+                    idx,
+                    Heading {
+                        level,
+                        line: trimmed,
+                        span: 2,
+                    },
+                ));
+                idx += 1;
+            }
+        }
+        idx += 1;
+    }
+    headings
+}
+
+/// Locate the first section whose heading satisfies `matches`
+/// Returns the line indices of the heading, the first body line, and the end of the section
+fn locate_section(
+    lines: &[&str],
+    matches: impl Fn(&Heading) -> bool,
+) -> Option<(usize, usize, usize)> {
+    let headings = find_headings(lines);
+    let pos = headings.iter().position(|(_, heading)| matches(heading))?;
+    let (start_idx, heading) = &headings[pos];
+
+    // The section ends at the next heading of equal or higher level
+    let end_idx = headings[pos + 1..]
+        .iter()
+        .find(|(_, next)| next.level <= heading.level)
+        .map(|(idx, _)| *idx)
+        .unwrap_or(lines.len());
+
+    Some((*start_idx, start_idx + heading.span, end_idx))
+}
+
 /// Extract a section from a markdown file
 /// The section starts at the given heading and continues until the next heading of equal or higher level
+/// Both ATX (`## Title`) and setext (`Title` underlined with `===` or `---`) headings are recognized
 pub fn extract_section(content: &str, section_heading: &str) -> MarkdownResult<String> {
     let lines: Vec<&str> = content.lines().collect();
+    let (start_idx, _, end_idx) =
+        locate_section(&lines, |heading| heading.matches(section_heading))
+            .ok_or_else(|| MarkdownError::SectionNotFound(section_heading.to_string()))?;
 
-    // Determine the heading level
-    let heading_level = section_heading.chars().take_while(|&c| c == '#').count();
+    Ok(lines[start_idx..end_idx].join("\n"))
+}
 
-    // Find the start of the section
-    let start_idx = lines
+/// Extract a section like [`extract_section`], but fail if the heading appears more than once
+/// [`extract_section`] silently takes the first match, which may be the wrong block
+pub fn extract_section_strict(content: &str, section_heading: &str) -> MarkdownResult<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let count = find_headings(&lines)
         .iter()
-        .position(|line| line.trim() == section_heading.trim())
-        .ok_or_else(|| MarkdownError::SectionNotFound(section_heading.to_string()))?;
+        .filter(|(_, heading)| heading.matches(section_heading))
+        .count();
+    if count > 1 {
+        return Err(MarkdownError::AmbiguousSection(section_heading.to_string()));
+    }
+    extract_section(content, section_heading)
+}
 
-    // Find the end of the section (next heading of equal or higher level)
-    let end_idx = lines[start_idx + 1..]
+/// Extract a section like [`extract_section`], but only down to `max_depth` levels of subheadings
+/// Subsections deeper than the matched level plus `max_depth` are left out, while later
+/// subsections within that depth are kept, so `max_depth = 0` gives the section's own text
+/// without any subsections
+pub fn extract_section_depth(
+    content: &str,
+    section_heading: &str,
+    max_depth: usize,
+) -> MarkdownResult<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let headings = find_headings(&lines);
+    let pos = headings
         .iter()
-        .position(|line| {
-            let trimmed = line.trim();
-            if trimmed.starts_with('#') {
-                let level = trimmed.chars().take_while(|&c| c == '#').count();
-                level <= heading_level
-            } else {
-                false
+        .position(|(_, heading)| heading.matches(section_heading))
+        .ok_or_else(|| MarkdownError::SectionNotFound(section_heading.to_string()))?;
+    let (start_idx, heading) = &headings[pos];
+    let max_level = heading.level.saturating_add(max_depth);
+
+    // Each subheading decides whether the lines up to the next one are kept
+    let mut subheadings = headings[pos + 1..].iter().peekable();
+    let mut keep = true;
+    let mut kept = Vec::new();
+    for (idx, line) in lines.iter().enumerate().skip(*start_idx) {
+        if let Some((_, next)) = subheadings.next_if(|(next_idx, _)| *next_idx == idx) {
+            if next.level <= heading.level {
+                break;
             }
-        })
-        .map(|i| start_idx + 1 + i)
-        .unwrap_or(lines.len());
+            keep = next.level <= max_level;
+        }
+        if keep {
+            kept.push(*line);
+        }
+    }
 
-    // Extract the section
-    let section_lines = &lines[start_idx..end_idx];
-    Ok(section_lines.join("\n"))
+    Ok(kept.join("\n"))
+}
+
+/// Extract a section like [`extract_section`], but without its heading line
+/// Blank lines around the remaining body are trimmed
+pub fn extract_section_body(content: &str, section_heading: &str) -> MarkdownResult<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let (_, body_idx, end_idx) = locate_section(&lines, |heading| heading.matches(section_heading))
+        .ok_or_else(|| MarkdownError::SectionNotFound(section_heading.to_string()))?;
+    let body = &lines[body_idx..end_idx];
+
+    let start = body.iter().position(|line| !line.trim().is_empty());
+    let end = body.iter().rposition(|line| !line.trim().is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => Ok(body[start..=end].join("\n")),
+        _ => Ok(String::new()),
+    }
+}
+
+/// Extract the first section whose heading text satisfies `predicate`
+/// The predicate receives the trimmed heading text without `#` markers, at any level
+pub fn extract_section_matching(
+    content: &str,
+    predicate: impl Fn(&str) -> bool,
+) -> MarkdownResult<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let (start_idx, _, end_idx) = locate_section(&lines, |heading| predicate(heading.text()))
+        .ok_or_else(|| MarkdownError::SectionNotFound("heading matching predicate".to_string()))?;
+
+    Ok(lines[start_idx..end_idx].join("\n"))
+}
+
+/// Extract the first section whose heading text starts with `prefix`, at any level
+/// A leading section number such as `1.` or `2.3` is ignored, so `"my-tool"` finds `### 2. my-tool`
+pub fn extract_section_prefix(content: &str, prefix: &str) -> MarkdownResult<String> {
+    let prefix = prefix.trim();
+    extract_section_matching(content, |text| {
+        text.starts_with(prefix) || strip_section_number(text).starts_with(prefix)
+    })
+    .map_err(|_| MarkdownError::SectionNotFound(format!("{}...", prefix)))
+}
+
+/// Remove a leading section number (`1.`, `2.3`, `4.1.`) from heading text
+fn strip_section_number(text: &str) -> &str {
+    let rest = text.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+    let number = &text[..text.len() - rest.len()];
+    if number.contains('.') && rest.starts_with(' ') {
+        rest.trim_start()
+    } else {
+        text
+    }
 }
 
 /// Extract multiple sections from a markdown file
@@ -66,6 +267,29 @@ pub fn extract_sections(content: &str, section_headings: &[String]) -> MarkdownR
     Ok(result.join("\n\n"))
 }
 
+/// Extract multiple sections like [`extract_sections`], but report every missing heading
+/// Returns all errors at once instead of stopping at the first one
+pub fn extract_sections_collecting(
+    content: &str,
+    section_headings: &[String],
+) -> Result<String, Vec<MarkdownError>> {
+    let mut result = Vec::new();
+    let mut errors = Vec::new();
+
+    for heading in section_headings {
+        match extract_section(content, heading) {
+            Ok(section) => result.push(section),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(result.join("\n\n"))
+    } else {
+        Err(errors)
+    }
+}
+
 /// Load markdown file and extract sections
 pub fn load_and_extract(
     path: impl AsRef<Path>,
@@ -129,6 +353,38 @@ Content 3
         assert!(result.contains("Content 3"));
     }
 
+    #[test]
+    fn test_extract_sections_collecting() {
+        let content = r#"# Heading 1
+Content 1
+
+## Heading 2
+Content 2
+"#;
+
+        let headings = vec![
+            "# Heading 1".to_string(),
+            "## Typo".to_string(),
+            "## Heading 2".to_string(),
+            "## Missing".to_string(),
+        ];
+        let errors = extract_sections_collecting(content, &headings).unwrap_err();
+        let missing: Vec<String> = errors
+            .iter()
+            .map(|e| match e {
+                MarkdownError::SectionNotFound(heading) => heading.clone(),
+                other => panic!("unexpected error: {}", other),
+            })
+            .collect();
+        assert_eq!(missing, vec!["## Typo", "## Missing"]);
+
+        let headings = vec!["# Heading 1".to_string(), "## Heading 2".to_string()];
+        assert_eq!(
+            extract_sections_collecting(content, &headings).unwrap(),
+            extract_sections(content, &headings).unwrap()
+        );
+    }
+
     #[test]
     fn test_extract_top_level_section() {
         let content = r#"# Heading 1
@@ -143,4 +399,223 @@ Content 2
         assert!(result.contains("Content 1"));
         assert!(!result.contains("# Heading 2"));
     }
+
+    #[test]
+    fn test_extract_section_body() {
+        let content = r#"# Heading 1
+
+Content 1
+
+## Heading 2
+
+Content 2
+
+### Heading 3
+Content 3
+
+## Heading 4
+Content 4
+"#;
+
+        let result = extract_section_body(content, "## Heading 2").unwrap();
+        assert!(!result.contains("## Heading 2"));
+        assert!(result.starts_with("Content 2"));
+        assert!(result.ends_with("Content 3"));
+
+        let result = extract_section_body(content, "# Heading 1").unwrap();
+        assert!(!result.contains("# Heading 1"));
+        assert!(result.starts_with("Content 1"));
+        assert!(result.ends_with("Content 4"));
+    }
+
+    #[test]
+    fn test_extract_section_strict() {
+        let content = r#"# Heading 1
+Content 1
+
+## Usage
+First usage
+
+# Heading 2
+
+## Usage
+Second usage
+
+## Unique
+Unique content
+"#;
+
+        let result = extract_section_strict(content, "## Unique").unwrap();
+        assert!(result.contains("Unique content"));
+
+        let err = extract_section_strict(content, "## Usage").unwrap_err();
+        assert!(matches!(err, MarkdownError::AmbiguousSection(ref h) if h == "## Usage"));
+
+        // Lenient extraction takes the first match
+        let result = extract_section(content, "## Usage").unwrap();
+        assert!(result.contains("First usage"));
+        assert!(!result.contains("Second usage"));
+
+        assert!(matches!(
+            extract_section_strict(content, "## Missing"),
+            Err(MarkdownError::SectionNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_extract_section_depth() {
+        let content = r#"# Heading 1
+Content 1
+
+## Heading 2
+Content 2
+
+### Heading 3
+Content 3
+
+#### Heading 4
+Content 4
+
+### Heading 5
+Content 5
+
+## Heading 6
+Content 6
+"#;
+
+        let result = extract_section_depth(content, "## Heading 2", 0).unwrap();
+        assert!(result.starts_with("## Heading 2"));
+        assert!(result.contains("Content 2"));
+        assert!(!result.contains("### Heading 3"));
+        assert!(!result.contains("Content 3"));
+
+        let result = extract_section_depth(content, "## Heading 2", 1).unwrap();
+        assert!(result.contains("### Heading 3"));
+        assert!(result.contains("Content 3"));
+        assert!(!result.contains("#### Heading 4"));
+        assert!(!result.contains("Content 4"));
+        // Only the too-deep subsection is dropped; later ones within the depth are kept
+        assert!(result.contains("### Heading 5"));
+        assert!(result.contains("Content 5"));
+        assert!(!result.contains("## Heading 6"));
+
+        let result = extract_section_depth(content, "## Heading 2", 2).unwrap();
+        assert_eq!(result, extract_section(content, "## Heading 2").unwrap());
+    }
+
+    #[test]
+    fn test_extract_setext_headings() {
+        let content = r#"Title
+=====
+Intro
+
+Usage
+-----
+Run it
+
+Details
+-------
+More
+"#;
+
+        let result = extract_section(content, "Title").unwrap();
+        assert!(result.starts_with("Title\n====="));
+        assert!(result.contains("Run it"));
+        assert!(result.contains("More"));
+
+        let result = extract_section(content, "Usage").unwrap();
+        assert!(result.contains("Run it"));
+        assert!(!result.contains("Details"));
+
+        assert_eq!(extract_section_body(content, "## Usage").unwrap(), "Run it");
+    }
+
+    #[test]
+    fn test_horizontal_rule_is_not_heading() {
+        let content = r#"---
+title: front matter
+---
+
+## Section
+Before rule
+
+---
+
+After rule
+
+## Next
+"#;
+
+        assert!(extract_section(content, "title: front matter").is_err());
+
+        let result = extract_section(content, "## Section").unwrap();
+        assert!(result.contains("Before rule"));
+        assert!(result.contains("After rule"));
+        assert!(!result.contains("## Next"));
+    }
+
+    #[test]
+    fn test_fenced_code_is_not_heading() {
+        let content = r#"## Config
+Example:
+
+```yaml
+key: v
+---
+other: w
+```
+
+~~~bash
+# install
+make
+~~~
+
+After the examples
+
+## Next
+"#;
+
+        assert!(extract_section(content, "key: v").is_err());
+        assert!(extract_section(content, "# install").is_err());
+
+        let result = extract_section(content, "## Config").unwrap();
+        assert!(result.contains("other: w"));
+        assert!(result.contains("After the examples"));
+        assert!(!result.contains("## Next"));
+    }
+
+    #[test]
+    fn test_extract_section_prefix() {
+        let content = r#"# Tools
+
+### 2. my-tool
+Does things
+
+### 3. other-tool
+Other things
+"#;
+
+        let result = extract_section_prefix(content, "my-tool").unwrap();
+        assert!(result.starts_with("### 2. my-tool"));
+        assert!(result.contains("Does things"));
+        assert!(!result.contains("other-tool"));
+
+        let result = extract_section_prefix(content, "3. other").unwrap();
+        assert!(result.contains("Other things"));
+
+        let result =
+            extract_section_matching(content, |text| text.ends_with("other-tool")).unwrap();
+        assert!(result.starts_with("### 3. other-tool"));
+    }
+
+    #[test]
+    fn test_extract_section_prefix_not_found() {
+        let content = "# Tools\n\n### 1. my-tool\nDoes things\n";
+
+        let result = extract_section_prefix(content, "missing-tool");
+        assert!(matches!(result, Err(MarkdownError::SectionNotFound(_))));
+
+        // Body text is not a heading
+        assert!(extract_section_prefix(content, "Does").is_err());
+    }
 }
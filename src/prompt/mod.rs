@@ -20,4 +20,4 @@ pub mod markdown;
 
 pub use builder::{PromptBuilder, PromptError, PromptResult};
 pub use config::{Config, ConfigError, ConfigResult, InitializeConfig, ToolConfig};
-pub use markdown::{extract_section, extract_sections, load_and_extract, MarkdownError, MarkdownResult};
+pub use markdown::{extract_section, extract_section_body, extract_section_depth, extract_section_matching, extract_section_prefix, extract_section_strict, extract_sections, extract_sections_collecting, load_and_extract, MarkdownError, MarkdownResult};
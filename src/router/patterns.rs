@@ -3,16 +3,53 @@
 //! This module demonstrates patterns for building MCP server routers
 //! with consistent error handling and progress tracking.
 
+use crate::extract::args::edit_distance;
+use crate::extract::FromToolCall;
+use crate::parse_value;
 use anyhow::{Context, Result};
 use std::collections::HashMap;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
 /// A tool handler function that takes S-expression arguments and returns a result.
 pub type ToolHandler = Box<dyn Fn(&str) -> Result<String> + Send + Sync>;
 
+/// A tool handler that can attach a progress event to its response.
+pub type ResultHandler = Box<dyn Fn(&str) -> Result<RouteResult> + Send + Sync>;
+
+/// A handler for tool names that match no registered tool or alias.
+///
+/// Receives the attempted tool name and the S-expression.
+pub type FallbackHandler = Box<dyn Fn(&str, &str) -> Result<String> + Send + Sync>;
+
+/// A hook run before every routed call, given the resolved tool name and the S-expression.
+pub type BeforeHook = Box<dyn Fn(&str, &str) + Send + Sync>;
+
+/// A hook run after every routed call, given the resolved tool name and the result.
+pub type AfterHook = Box<dyn Fn(&str, &Result<String>) + Send + Sync>;
+
+/// In-memory call counters for one tool, collected by [`Router::with_metrics`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolMetrics {
+    /// Number of routed calls.
+    pub calls: u64,
+    /// Number of calls that returned an error.
+    pub errors: u64,
+    /// Time spent in the handler across all calls.
+    pub total_duration: Duration,
+}
+
 /// A router that maps tool names to handler functions.
 pub struct Router {
-    handlers: HashMap<String, ToolHandler>,
+    handlers: HashMap<String, ResultHandler>,
+    descriptions: HashMap<String, String>,
     aliases: HashMap<String, String>,
+    fallback: Option<FallbackHandler>,
+    before: Option<BeforeHook>,
+    after: Option<AfterHook>,
+    mounts: HashMap<String, Router>,
+    metrics: Option<Arc<Mutex<HashMap<String, ToolMetrics>>>>,
 }
 
 impl Router {
@@ -20,16 +57,116 @@ impl Router {
     pub fn new() -> Self {
         Self {
             handlers: HashMap::new(),
+            descriptions: HashMap::new(),
             aliases: HashMap::new(),
+            fallback: None,
+            before: None,
+            after: None,
+            mounts: HashMap::new(),
+            metrics: None,
+        }
+    }
+
+    /// Count calls, errors and handler time per canonical tool name.
+    ///
+    /// Only registered tools are counted; calls to unknown names, including
+    /// those answered by the fallback, leave the counters untouched so that
+    /// arbitrary client input cannot grow them. Read the counters with
+    /// [`Router::metrics_snapshot`].
+    pub fn with_metrics(mut self) -> Self {
+        self.metrics = Some(Arc::new(Mutex::new(HashMap::new())));
+        self
+    }
+
+    /// Copy of the current per-tool counters; empty unless metrics are enabled.
+    pub fn metrics_snapshot(&self) -> HashMap<String, ToolMetrics> {
+        match &self.metrics {
+            Some(metrics) => metrics.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+            None => HashMap::new(),
         }
     }
 
+    fn record_metrics(&self, canonical_name: &str, elapsed: Duration, is_error: bool) {
+        let Some(metrics) = &self.metrics else {
+            return;
+        };
+        let mut metrics = metrics.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = metrics.entry(canonical_name.to_string()).or_default();
+        entry.calls += 1;
+        entry.total_duration += elapsed;
+        if is_error {
+            entry.errors += 1;
+        }
+    }
+
+    /// Set a hook that runs before every routed call.
+    pub fn with_before<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&str, &str) + Send + Sync + 'static,
+    {
+        self.before = Some(Box::new(hook));
+        self
+    }
+
+    /// Set a hook that runs after every routed call, including failed ones.
+    ///
+    /// This is a natural place to record a `ToolCallEvent`.
+    pub fn with_after<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&str, &Result<String>) + Send + Sync + 'static,
+    {
+        self.after = Some(Box::new(hook));
+        self
+    }
+
     /// Register a tool handler.
     pub fn register<F>(&mut self, tool_name: impl Into<String>, handler: F)
     where
         F: Fn(&str) -> Result<String> + Send + Sync + 'static,
     {
-        self.handlers.insert(tool_name.into(), Box::new(handler));
+        self.register_with_result(tool_name, move |sexpr| handler(sexpr).map(RouteResult::new));
+    }
+
+    /// Register a tool handler with a one-line description for [`Router::describe`].
+    pub fn register_with_desc<F>(
+        &mut self,
+        tool_name: impl Into<String>,
+        description: impl Into<String>,
+        handler: F,
+    ) where
+        F: Fn(&str) -> Result<String> + Send + Sync + 'static,
+    {
+        let tool_name = tool_name.into();
+        self.register(tool_name.clone(), handler);
+        self.descriptions.insert(tool_name, description.into());
+    }
+
+    /// Register a tool handler that returns a [`RouteResult`], so it can
+    /// attach a progress event for [`Router::route_with_result`].
+    pub fn register_with_result<F>(&mut self, tool_name: impl Into<String>, handler: F)
+    where
+        F: Fn(&str) -> Result<RouteResult> + Send + Sync + 'static,
+    {
+        let tool_name = tool_name.into();
+        // Re-registering without a description drops the old one
+        self.descriptions.remove(&tool_name);
+        self.handlers.insert(tool_name, Box::new(handler));
+    }
+
+    /// Register a handler that receives typed arguments instead of the raw S-expression.
+    ///
+    /// The router parses the call and builds `A` with [`FromToolCall`] (for
+    /// example via `#[derive(Deserialize)]`) before invoking `handler`.
+    pub fn register_typed<A, F>(&mut self, tool_name: impl Into<String>, handler: F)
+    where
+        A: FromToolCall + 'static,
+        F: Fn(A) -> Result<String> + Send + Sync + 'static,
+    {
+        self.register(tool_name, move |sexpr| {
+            let value = parse_value(sexpr)?;
+            let args = A::from_tool_call(&value)?;
+            handler(args)
+        });
     }
 
     /// Register an alias for a tool.
@@ -37,29 +174,231 @@ impl Router {
         self.aliases.insert(alias.into(), canonical.into());
     }
 
+    /// Remove a tool handler. Returns `true` if the tool was registered.
+    ///
+    /// Aliases pointing at the removed tool are kept; routing through them
+    /// fails as an unknown tool until the tool is registered again.
+    pub fn unregister(&mut self, tool_name: &str) -> bool {
+        self.descriptions.remove(tool_name);
+        self.handlers.remove(tool_name).is_some()
+    }
+
+    /// Remove an alias. Returns `true` if the alias was registered.
+    pub fn unregister_alias(&mut self, alias: &str) -> bool {
+        self.aliases.remove(alias).is_some()
+    }
+
+    /// Get all registered aliases as `(alias, canonical)` pairs, sorted by alias.
+    pub fn aliases(&self) -> Vec<(String, String)> {
+        let mut pairs: Vec<(String, String)> = self
+            .aliases
+            .iter()
+            .map(|(alias, canonical)| (alias.clone(), canonical.clone()))
+            .collect();
+        pairs.sort();
+        pairs
+    }
+
+    /// Mount a sub-router under `prefix`, so `prefix/name` routes to the
+    /// sub-router's `name` tool.
+    ///
+    /// Names the sub-router does not know fall through to this router's own
+    /// handlers and fallback. Mounting again under the same prefix replaces
+    /// the previous sub-router.
+    pub fn mount(&mut self, prefix: impl Into<String>, sub: Router) {
+        self.mounts.insert(prefix.into(), sub);
+    }
+
+    /// Find the mounted sub-router that handles `tool_name`, with the prefix stripped.
+    fn resolve_mount<'a>(&self, tool_name: &'a str) -> Option<(&Router, &'a str)> {
+        let (prefix, rest) = tool_name.split_once('/')?;
+        let sub = self.mounts.get(prefix)?;
+        sub.has_tool(rest).then_some((sub, rest))
+    }
+
+    /// Set a handler that is called instead of erroring for unknown tools.
+    pub fn set_fallback<F>(&mut self, handler: F)
+    where
+        F: Fn(&str, &str) -> Result<String> + Send + Sync + 'static,
+    {
+        self.fallback = Some(Box::new(handler));
+    }
+
     /// Route a tool call to its handler.
+    ///
+    /// The before and after hooks, if set, run around the call.
     pub fn route(&self, tool_name: &str, sexpr: &str) -> Result<String> {
+        self.route_with_result(tool_name, sexpr)
+            .map(|result| result.response)
+    }
+
+    /// Route a tool call and return the full [`RouteResult`], including any
+    /// progress event attached by the handler.
+    ///
+    /// Handlers registered with [`Router::register`] never attach a progress event.
+    pub fn route_with_result(&self, tool_name: &str, sexpr: &str) -> Result<RouteResult> {
         // Resolve alias if present
-        let canonical_name = self.aliases.get(tool_name).map(|s| s.as_str()).unwrap_or(tool_name);
+        let canonical_name = self
+            .aliases
+            .get(tool_name)
+            .map(|s| s.as_str())
+            .unwrap_or(tool_name);
 
-        // Find and call handler
-        let handler = self
-            .handlers
-            .get(canonical_name)
-            .ok_or_else(|| anyhow::anyhow!("Unknown tool: {}", tool_name))?;
+        if let Some(before) = &self.before {
+            before(canonical_name, sexpr);
+        }
+
+        let registered = self.has_tool(tool_name);
+        let started = Instant::now();
+        let (result, progress_event) = match self.dispatch(tool_name, canonical_name, sexpr) {
+            Ok(routed) => (Ok(routed.response), routed.progress_event),
+            Err(e) => (Err(e), None),
+        };
+        if registered {
+            self.record_metrics(canonical_name, started.elapsed(), result.is_err());
+        }
+
+        if let Some(after) = &self.after {
+            after(canonical_name, &result);
+        }
+
+        result.map(|response| RouteResult {
+            response,
+            progress_event,
+        })
+    }
+
+    /// Route a tool call on a worker thread, giving up after `timeout`.
+    ///
+    /// Returns a timeout error if the call has not finished in time. The
+    /// worker thread cannot be cancelled: a handler that never returns keeps
+    /// its thread (and a clone of the router `Arc`) alive for the life of the
+    /// process, and the after hook still runs whenever the handler does
+    /// finish.
+    pub fn route_with_timeout(
+        self: &Arc<Self>,
+        tool_name: &str,
+        sexpr: &str,
+        timeout: Duration,
+    ) -> Result<String> {
+        let (tx, rx) = mpsc::channel();
+        let router = Arc::clone(self);
+        let tool = tool_name.to_string();
+        let args = sexpr.to_string();
+        thread::Builder::new()
+            .name(format!("route-{}", tool_name))
+            .spawn(move || {
+                // The receiver is gone if the caller already timed out
+                let _ = tx.send(router.route(&tool, &args));
+            })
+            .context("Failed to spawn router worker thread")?;
+
+        match rx.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(anyhow::anyhow!(
+                "Tool {} timed out after {:?}",
+                tool_name,
+                timeout
+            )),
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                Err(anyhow::anyhow!("Tool {} panicked", tool_name))
+            }
+        }
+    }
+
+    fn dispatch(&self, tool_name: &str, canonical_name: &str, sexpr: &str) -> Result<RouteResult> {
+        if let Some((sub, rest)) = self.resolve_mount(canonical_name) {
+            return sub.route_with_result(rest, sexpr);
+        }
+
+        // Find and call handler, falling back for unknown tools if configured
+        let Some(handler) = self.handlers.get(canonical_name) else {
+            return match &self.fallback {
+                Some(fallback) => fallback(tool_name, sexpr).map(RouteResult::new),
+                None => Err(match self.suggest(tool_name) {
+                    Some(name) => {
+                        anyhow::anyhow!("Unknown tool: {} (did you mean '{}'?)", tool_name, name)
+                    }
+                    None => anyhow::anyhow!("Unknown tool: {}", tool_name),
+                }),
+            };
+        };
 
         handler(sexpr).with_context(|| format!("Error executing tool: {}", tool_name))
     }
 
+    /// Closest registered tool or alias name within a small edit distance.
+    fn suggest(&self, tool_name: &str) -> Option<&str> {
+        self.handlers
+            .keys()
+            .chain(self.aliases.keys())
+            .map(|name| (edit_distance(tool_name, name), name.as_str()))
+            .filter(|(distance, _)| *distance <= 2)
+            .min()
+            .map(|(_, name)| name)
+    }
+
+    /// Route a tool call, taking the tool name from the head symbol of the form.
+    ///
+    /// Aliases are resolved the same way as in [`Router::route`].
+    pub fn route_sexpr(&self, sexpr: &str) -> Result<String> {
+        let value = parse_value(sexpr)?;
+        let list = value
+            .as_cons()
+            .ok_or_else(|| anyhow::anyhow!("Tool call must be a list form: {}", sexpr))?;
+        let tool_name = list
+            .car()
+            .as_symbol()
+            .ok_or_else(|| anyhow::anyhow!("Tool call head must be a symbol: {}", sexpr))?;
+
+        self.route(tool_name, sexpr)
+    }
+
     /// Get all registered tool names (excluding aliases).
+    ///
+    /// Tools of mounted sub-routers are listed as `prefix/name`.
     pub fn tool_names(&self) -> Vec<String> {
-        self.handlers.keys().cloned().collect()
+        let mut names: Vec<String> = self.handlers.keys().cloned().collect();
+        for (prefix, sub) in &self.mounts {
+            names.extend(
+                sub.tool_names()
+                    .into_iter()
+                    .map(|name| format!("{}/{}", prefix, name)),
+            );
+        }
+        names
+    }
+
+    /// Get all registered tool names with their descriptions, sorted by name.
+    ///
+    /// Tools registered without [`Router::register_with_desc`] have `None`.
+    /// Mounted sub-routers are included as `prefix/name`, like in
+    /// [`Router::tool_names`]; aliases are not.
+    pub fn describe(&self) -> Vec<(String, Option<String>)> {
+        let mut entries: Vec<(String, Option<String>)> = self
+            .handlers
+            .keys()
+            .map(|name| (name.clone(), self.descriptions.get(name).cloned()))
+            .collect();
+        for (prefix, sub) in &self.mounts {
+            entries.extend(
+                sub.describe()
+                    .into_iter()
+                    .map(|(name, description)| (format!("{}/{}", prefix, name), description)),
+            );
+        }
+        entries.sort();
+        entries
     }
 
     /// Check if a tool is registered.
     pub fn has_tool(&self, tool_name: &str) -> bool {
-        let canonical_name = self.aliases.get(tool_name).map(|s| s.as_str()).unwrap_or(tool_name);
-        self.handlers.contains_key(canonical_name)
+        let canonical_name = self
+            .aliases
+            .get(tool_name)
+            .map(|s| s.as_str())
+            .unwrap_or(tool_name);
+        self.handlers.contains_key(canonical_name) || self.resolve_mount(canonical_name).is_some()
     }
 }
 
@@ -127,6 +466,63 @@ mod tests {
         assert_eq!(result, "(success)");
     }
 
+    #[test]
+    fn test_route_with_timeout() {
+        let mut router = Router::new();
+        router.register("fast", |_| Ok("(success)".to_string()));
+        router.register("slow", |_| {
+            thread::sleep(Duration::from_millis(500));
+            Ok("(success)".to_string())
+        });
+        let router = Arc::new(router);
+
+        let fast = router.route_with_timeout("fast", "(fast)", Duration::from_secs(5));
+        assert_eq!(fast.unwrap(), "(success)");
+
+        let slow = router.route_with_timeout("slow", "(slow)", Duration::from_millis(20));
+        assert!(slow.unwrap_err().to_string().contains("timed out"));
+
+        let unknown = router.route_with_timeout("nope", "(nope)", Duration::from_secs(5));
+        assert!(unknown.unwrap_err().to_string().contains("Unknown tool"));
+    }
+
+    #[test]
+    fn test_router_metrics() {
+        let mut router = Router::new().with_metrics();
+        router.register("check", |args| {
+            if args.contains(":fail") {
+                Err(anyhow::anyhow!("failed"))
+            } else {
+                Ok("(success)".to_string())
+            }
+        });
+        router.register_alias("c", "check");
+
+        router.route("check", "(check)").unwrap();
+        assert!(router.route("c", "(c :fail #t)").is_err());
+
+        let metrics = router.metrics_snapshot();
+        assert_eq!(metrics.len(), 1);
+        let check = &metrics["check"];
+        assert_eq!(check.calls, 2);
+        assert_eq!(check.errors, 1);
+
+        assert!(Router::new().metrics_snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_router_metrics_skip_unknown_tools() {
+        let mut router = Router::new().with_metrics();
+        router.register("check", |_| Ok("(success)".to_string()));
+
+        assert!(router.route("chekc", "(chekc)").is_err());
+        assert!(router.metrics_snapshot().is_empty());
+
+        router.set_fallback(|_, _| Ok("(fallback)".to_string()));
+        router.route("anything", "(anything)").unwrap();
+        assert!(router.metrics_snapshot().is_empty());
+    }
+
     #[test]
     fn test_router_unknown_tool() {
         let router = Router::new();
@@ -135,6 +531,175 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("Unknown tool"));
     }
 
+    #[test]
+    fn test_router_unknown_tool_suggestion() {
+        let mut router = Router::new();
+        router.register("commit", |_| Ok("(success)".to_string()));
+        router.register("status", |_| Ok("(success)".to_string()));
+
+        let err = router.route("comit", "()").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown tool: comit (did you mean 'commit'?)"
+        );
+
+        let err = router.route("deploy", "()").unwrap_err();
+        assert_eq!(err.to_string(), "Unknown tool: deploy");
+    }
+
+    #[test]
+    fn test_route_sexpr() {
+        let mut router = Router::new();
+        router.register("echo", |args| Ok(format!("(success :echo {})", args)));
+        router.register_alias("say", "echo");
+
+        let result = router.route_sexpr("(echo :msg \"hello\")").unwrap();
+        assert!(result.contains("hello"));
+
+        let result = router.route_sexpr("(say :msg \"hi\")").unwrap();
+        assert!(result.contains("hi"));
+    }
+
+    #[test]
+    fn test_route_sexpr_malformed() {
+        let mut router = Router::new();
+        router.register("echo", |args| Ok(args.to_string()));
+
+        let err = router.route_sexpr("\"not a list\"").unwrap_err();
+        assert!(err.to_string().contains("list form"));
+
+        let err = router.route_sexpr("(\"echo\" :msg 1)").unwrap_err();
+        assert!(err.to_string().contains("symbol"));
+
+        assert!(router.route_sexpr("(echo").is_err());
+    }
+
+    #[test]
+    fn test_router_hooks_order() {
+        use std::sync::{Arc, Mutex};
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let (before_log, after_log, handler_log) = (log.clone(), log.clone(), log.clone());
+
+        let mut router = Router::new()
+            .with_before(move |name, sexpr| {
+                before_log
+                    .lock()
+                    .unwrap()
+                    .push(format!("before {} {}", name, sexpr));
+            })
+            .with_after(move |name, result| {
+                after_log
+                    .lock()
+                    .unwrap()
+                    .push(format!("after {} ok={}", name, result.is_ok()));
+            });
+        router.register("ok", move |_| {
+            handler_log.lock().unwrap().push("handler".to_string());
+            Ok("(success)".to_string())
+        });
+        router.register("fail", |_| Err(anyhow::anyhow!("boom")));
+        router.register_alias("alias", "ok");
+
+        router.route("alias", "(alias)").unwrap();
+        assert!(router.route("fail", "(fail)").is_err());
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "before ok (alias)".to_string(),
+                "handler".to_string(),
+                "after ok ok=true".to_string(),
+                "before fail (fail)".to_string(),
+                "after fail ok=false".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_route_with_result_progress() {
+        let mut router = Router::new();
+        router.register_with_result("index", |_| {
+            Ok(RouteResult::with_progress(
+                "(success)".to_string(),
+                "index",
+                "indexed 3 files".to_string(),
+            ))
+        });
+        router.register("plain", |_| Ok("(success)".to_string()));
+
+        let result = router.route_with_result("index", "(index)").unwrap();
+        assert_eq!(result.response, "(success)");
+        let event = result.progress_event.unwrap();
+        assert_eq!(event.tool_name, "index");
+        assert_eq!(event.context, "indexed 3 files");
+
+        let result = router.route_with_result("plain", "(plain)").unwrap();
+        assert!(result.progress_event.is_none());
+
+        assert_eq!(router.route("index", "(index)").unwrap(), "(success)");
+    }
+
+    #[test]
+    fn test_router_fallback() {
+        let mut router = Router::new();
+        router.register("known", |_| Ok("(success :from known)".to_string()));
+        router.set_fallback(|name, sexpr| Ok(format!("(fallback {} {})", name, sexpr)));
+
+        let result = router.route("mystery", "(mystery :x 1)").unwrap();
+        assert_eq!(result, "(fallback mystery (mystery :x 1))");
+
+        let result = router.route("known", "(known)").unwrap();
+        assert_eq!(result, "(success :from known)");
+    }
+
+    #[test]
+    fn test_router_unregister() {
+        let mut router = Router::new();
+        router.register("tool", |_| Ok("(success)".to_string()));
+        router.register_alias("t", "tool");
+        router.register_alias("alt", "tool");
+
+        assert_eq!(
+            router.aliases(),
+            vec![
+                ("alt".to_string(), "tool".to_string()),
+                ("t".to_string(), "tool".to_string()),
+            ]
+        );
+
+        assert!(router.unregister_alias("alt"));
+        assert!(!router.unregister_alias("alt"));
+        assert_eq!(router.aliases().len(), 1);
+
+        assert!(router.unregister("tool"));
+        assert!(!router.unregister("tool"));
+        assert!(!router.has_tool("tool"));
+        assert!(!router.has_tool("t"));
+        assert!(router.route("t", "(t)").is_err());
+        assert!(router.route("tool", "(tool)").is_err());
+    }
+
+    #[test]
+    fn test_register_typed() {
+        #[derive(serde::Deserialize)]
+        struct AddArgs {
+            a: i64,
+            b: i64,
+        }
+
+        let mut router = Router::new();
+        router.register_typed("add", |args: AddArgs| {
+            Ok(format!("(success :sum {})", args.a + args.b))
+        });
+
+        let result = router.route("add", "(add :a 2 :b 40)").unwrap();
+        assert_eq!(result, "(success :sum 42)");
+
+        let err = router.route("add", "(add :a 2)").unwrap_err();
+        assert!(format!("{:#}", err).contains("b"));
+    }
+
     #[test]
     fn test_router_tool_names() {
         let mut router = Router::new();
@@ -147,6 +712,38 @@ mod tests {
         assert!(names.contains(&"tool2".to_string()));
     }
 
+    #[test]
+    fn test_router_describe() {
+        let mut git = Router::new();
+        git.register_with_desc("commit", "Record changes", |_| Ok("()".to_string()));
+
+        let mut router = Router::new();
+        router.register_with_desc("status", "Show working tree status", |_| {
+            Ok("()".to_string())
+        });
+        router.register("apply", |_| Ok("()".to_string()));
+        router.register_alias("st", "status");
+        router.mount("git", git);
+
+        assert_eq!(
+            router.describe(),
+            vec![
+                ("apply".to_string(), None),
+                ("git/commit".to_string(), Some("Record changes".to_string())),
+                (
+                    "status".to_string(),
+                    Some("Show working tree status".to_string())
+                ),
+            ]
+        );
+
+        // Described tools still route normally
+        assert_eq!(router.route("st", "(st)").unwrap(), "()");
+
+        router.register("status", |_| Ok("()".to_string()));
+        assert_eq!(router.describe()[2], ("status".to_string(), None));
+    }
+
     #[test]
     fn test_has_tool() {
         let mut router = Router::new();
@@ -155,4 +752,29 @@ mod tests {
         assert!(router.has_tool("existing"));
         assert!(!router.has_tool("nonexistent"));
     }
+
+    #[test]
+    fn test_router_mount() {
+        let mut git = Router::new();
+        git.register("commit", |args| Ok(format!("(success :commit {})", args)));
+
+        let mut router = Router::new();
+        router.register("status", |_| Ok("(success :status \"clean\")".to_string()));
+        router.mount("git", git);
+
+        let result = router.route("git/commit", "(commit :msg \"x\")").unwrap();
+        assert!(result.contains(":commit"));
+        assert!(router.has_tool("git/commit"));
+        assert!(!router.has_tool("commit"));
+
+        // Non-prefixed tools still resolve at the top level
+        assert_eq!(
+            router.route("status", "()").unwrap(),
+            "(success :status \"clean\")"
+        );
+
+        // Unknown names under the prefix fall through to the parent
+        let err = router.route("git/push", "()").unwrap_err();
+        assert!(err.to_string().contains("Unknown tool: git/push"));
+    }
 }